@@ -0,0 +1,148 @@
+//! provides functionalities for commands to be executed by the system, such as
+//! when the application starts or after the application updates.
+//!
+use crate::{mt_dom::Callback, Dispatch};
+use std::{marker::PhantomData, rc::Rc};
+
+/// A command function, which is supplied with the dispatcher when executed
+pub type Command<MSG> = Rc<dyn Fn(Rc<dyn Dispatch<MSG>>)>;
+
+/// Cmd is a command to be executed by the system.
+/// This is returned at the init function of a component and is executed right
+/// after instantiation of that component.
+/// Cmd required a dispatcher object which is the Program as an argument
+/// The emit function is called with the program argument.
+/// The callback is supplied with the program an is then executed/emitted.
+///
+/// The commands are only given a dispatcher for MSG, instead of the concrete Program,
+/// so that a Cmd of a child component can be mapped into the Cmd of the parent component
+/// using `map_msg`.
+pub struct Cmd<APP, MSG> {
+    /// the functions that would be executed when this Cmd is emited
+    pub commands: Vec<Command<MSG>>,
+    /// indicates if the view should be re-rendered after the update,
+    /// the Program skips the view and the dom update if this is false
+    pub should_update: bool,
+    _phantom_app: PhantomData<APP>,
+}
+
+impl<APP, MSG> Cmd<APP, MSG>
+where
+    MSG: 'static,
+{
+    /// creates a new Cmd from a function
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(Rc<dyn Dispatch<MSG>>) + 'static,
+    {
+        Self::from_commands(vec![Rc::new(f)])
+    }
+
+    fn from_commands(commands: Vec<Command<MSG>>) -> Self {
+        Cmd {
+            commands,
+            should_update: true,
+            _phantom_app: PhantomData,
+        }
+    }
+
+    /// creates a unified Cmd which batches all the other Cmds in one.
+    /// The view is skipped only if all of the Cmds skip it.
+    pub fn batch(cmds: Vec<Self>) -> Self {
+        let should_update =
+            cmds.is_empty() || cmds.iter().any(|cmd| cmd.should_update);
+        let mut commands = vec![];
+        for cmd in cmds {
+            commands.extend(cmd.commands);
+        }
+        Self::from_commands(commands).should_update(should_update)
+    }
+
+    /// A Cmd with no callback, similar to NoOp.
+    pub fn none() -> Self {
+        Self::from_commands(vec![])
+    }
+
+    /// A Cmd with no callback, which also tells the Program that
+    /// there is no need to re-render the view since nothing has changed
+    /// in the app, ie: ignored key presses.
+    pub fn no_render() -> Self {
+        Self::none().should_update(false)
+    }
+
+    /// set whether the Program should re-render the view
+    /// after the update which returns this Cmd.
+    pub fn should_update(mut self, should_update: bool) -> Self {
+        self.should_update = should_update;
+        self
+    }
+
+    /// Executes the Cmd
+    pub fn emit<DSP>(self, program: &DSP)
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let program: Rc<dyn Dispatch<MSG>> = Rc::new(program.clone());
+        for cb in self.commands {
+            cb(Rc::clone(&program));
+        }
+    }
+
+    /// map the msg of this Cmd such that it can be dispatched
+    /// by the program of the parent component.
+    ///
+    /// Example:
+    /// ```rust,ignore
+    /// Msg::NavHeaderMsg(header_msg) => {
+    ///     let cmd: Cmd<NavHeader, nav_header::Msg> = self.nav_header.update(header_msg);
+    ///     cmd.map_msg(Msg::NavHeaderMsg)
+    /// }
+    /// ```
+    pub fn map_msg<F, APP2, MSG2>(self, f: F) -> Cmd<APP2, MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        let mapper: Callback<MSG, MSG2> = Callback::from(f);
+        let commands = self
+            .commands
+            .into_iter()
+            .map(|cb| {
+                let mapper = mapper.clone();
+                let mapped_cb: Command<MSG2> =
+                    Rc::new(move |program: Rc<dyn Dispatch<MSG2>>| {
+                        let map_dispatch: Rc<dyn Dispatch<MSG>> =
+                            Rc::new(MapDispatch {
+                                program,
+                                mapper: mapper.clone(),
+                            });
+                        cb(map_dispatch)
+                    });
+                mapped_cb
+            })
+            .collect();
+        Cmd::from_commands(commands).should_update(self.should_update)
+    }
+}
+
+/// Dispatches the MSG of a child component into the program of the parent component
+/// by mapping it to the parent MSG2 first.
+pub(crate) struct MapDispatch<MSG, MSG2> {
+    pub(crate) program: Rc<dyn Dispatch<MSG2>>,
+    pub(crate) mapper: Callback<MSG, MSG2>,
+}
+
+impl<MSG, MSG2> Dispatch<MSG> for MapDispatch<MSG, MSG2>
+where
+    MSG: 'static,
+    MSG2: 'static,
+{
+    fn dispatch(&self, msg: MSG) {
+        self.program.dispatch(self.mapper.emit(msg))
+    }
+
+    #[cfg(feature = "with-dom")]
+    fn report_error(&self, error: crate::dom::DomError) {
+        self.program.report_error(error)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Cmd, Node};
+use crate::{Cmd, Node, Sub};
 
 /// The app should implement this trait for it to be handled by the Program
 pub trait Component<MSG>
@@ -26,4 +26,12 @@ where
 
     /// Returns a node on how the component is presented.
     fn view(&self) -> Node<MSG>;
+
+    /// The events outside of the view that this component listens to,
+    /// such as window events or interval timers.
+    /// This is evaluated again after every update, listeners which are no
+    /// longer in the subscriptions are removed.
+    fn subscriptions(&self) -> Sub<MSG> {
+        Sub::none()
+    }
 }
@@ -0,0 +1,109 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// This trait is used in the DomUpdater to call the dispatch
+/// method when an event occured
+///
+/// The Program will implement Dispatch instead of sending it to the
+/// DomUpdater, this will simplify the amount of generics being defined.
+pub trait Dispatch<MSG> {
+    /// Executes the implementing dispatch function.
+    /// In this case the implementation is the Program
+    /// which is responsible for executing the update functions
+    /// using the msg supplied.
+    /// A new view will then be created and it will be diff to the previous view
+    /// which will produce patches.
+    /// These patched will then be applied to the browser DOM.
+    fn dispatch(&self, msg: MSG);
+
+    /// Report an error which happened in the DOM, such as an event with an
+    /// unexpected target, instead of aborting the app.
+    /// The errors are logged by default, while the Program reports them to
+    /// its error hook.
+    #[cfg(feature = "with-dom")]
+    fn report_error(&self, error: crate::dom::DomError) {
+        log::error!("{}", error);
+    }
+}
+
+/// A Dispatch wrapped in Rc is also a Dispatch, this allows the Cmd
+/// to pass around the program without knowing its concrete type.
+impl<MSG, DSP> Dispatch<MSG> for Rc<DSP>
+where
+    DSP: Dispatch<MSG> + ?Sized,
+{
+    fn dispatch(&self, msg: MSG) {
+        (**self).dispatch(msg)
+    }
+
+    #[cfg(feature = "with-dom")]
+    fn report_error(&self, error: crate::dom::DomError) {
+        (**self).report_error(error)
+    }
+}
+
+/// A dispatcher which doesn't need a browser.
+/// The dispatched msgs are queued instead of being executed right away,
+/// so they can be processed later, such as in the server or in a unit test.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::{Cmd, Dispatch, MsgQueue};
+///
+/// let cmd: Cmd<(), i32> = Cmd::new(|program| program.dispatch(1));
+/// let queue = MsgQueue::new();
+/// cmd.emit(&queue);
+/// assert_eq!(queue.take(), vec![1]);
+/// ```
+pub struct MsgQueue<MSG> {
+    msgs: Rc<RefCell<VecDeque<MSG>>>,
+}
+
+impl<MSG> MsgQueue<MSG> {
+    /// create an empty queue
+    pub fn new() -> Self {
+        MsgQueue {
+            msgs: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    /// remove and return the msg which is dispatched the earliest
+    pub fn pop(&self) -> Option<MSG> {
+        self.msgs.borrow_mut().pop_front()
+    }
+
+    /// remove and return all the queued msgs, in the order they are dispatched
+    pub fn take(&self) -> Vec<MSG> {
+        self.msgs.borrow_mut().drain(..).collect()
+    }
+
+    /// the number of msgs in the queue
+    pub fn len(&self) -> usize {
+        self.msgs.borrow().len()
+    }
+
+    /// returns true if there are no msgs in the queue
+    pub fn is_empty(&self) -> bool {
+        self.msgs.borrow().is_empty()
+    }
+}
+
+impl<MSG> Default for MsgQueue<MSG> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// the clones share the same queue
+impl<MSG> Clone for MsgQueue<MSG> {
+    fn clone(&self) -> Self {
+        MsgQueue {
+            msgs: Rc::clone(&self.msgs),
+        }
+    }
+}
+
+impl<MSG> Dispatch<MSG> for MsgQueue<MSG> {
+    fn dispatch(&self, msg: MSG) {
+        self.msgs.borrow_mut().push_back(msg);
+    }
+}
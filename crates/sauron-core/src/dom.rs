@@ -3,30 +3,36 @@
 //!
 pub mod apply_patches;
 mod browser;
-pub mod cmd;
-mod component;
 mod created_node;
-mod dispatch;
 mod dom_updater;
+mod error;
 pub mod events;
+mod event_source;
 mod http;
 mod program;
+mod storage;
+mod sub_component;
+mod subscriptions;
+mod timer;
 mod util;
+mod websocket;
 mod window;
 
+pub use crate::{cmd, Cmd, Component, Dispatch};
 pub use browser::Browser;
-pub use component::Component;
 pub use created_node::CreatedNode;
-pub use dispatch::Dispatch;
 pub use dom_updater::DomUpdater;
+pub use error::DomError;
+pub use event_source::{EventSource, ServerMessage, StreamState};
 pub use events::*;
-pub use http::Http;
+pub use http::{Http, HttpError, HttpRequest, HttpResponse, RequestHandle};
 pub use program::Program;
+pub use storage::{Storage, StorageArea, StorageError};
+pub use sub_component::SubComponent;
+pub use timer::{Timer, TimerHandle};
 pub use util::{
     body, document, execute_in_request_animation_frame, history, now,
     performance, request_animation_frame, window,
 };
+pub use websocket::{Backoff, SocketEvent, SocketMessage, WebSocket};
 pub use window::Window;
-
-/// alias Cmd to use Program as the APP
-pub type Cmd<APP, MSG> = cmd::Cmd<Program<APP, MSG>>;
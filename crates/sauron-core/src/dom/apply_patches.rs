@@ -6,6 +6,8 @@ use crate::{
             ActiveClosure,
             CreatedNode,
         },
+        sub_component::DATA_SAURON_COMPONENT,
+        DomError,
     },
     mt_dom::{
         patch::{
@@ -20,14 +22,13 @@ use crate::{
         NodeIdx,
     },
     Dispatch,
+    DomPatch,
+    MoveTarget,
     Patch,
 };
 use js_sys::Function;
 use std::collections::HashMap;
-use wasm_bindgen::{
-    JsCast,
-    JsValue,
-};
+use wasm_bindgen::JsCast;
 use web_sys::{
     Element,
     Node,
@@ -37,34 +38,70 @@ use web_sys::{
 /// that we desire.
 /// This is usually used after diffing two virtual nodes.
 ///
+/// The patches can be either the `Patch` from `diff` or the `DomPatch` from
+/// `diff_with_moves`, which relocates the existing keyed nodes.
+///
 /// Note: If Program is None, it is a dumb patch, meaning
 /// there is no event listener attached or changed
-pub fn patch<N, DSP, MSG>(
+///
+/// Returns an error when a patch can not be applied, such as when the node to
+/// be patched is not found. The patches before it are already applied, so
+/// the error comes together with the closures of their event listeners,
+/// which are still attached to the DOM.
+pub fn patch<'a, N, DSP, MSG, P>(
     program: Option<&DSP>,
     root_node: N,
     old_closures: &mut ActiveClosure,
     node_idx_lookup: &mut HashMap<NodeIdx, Node>,
     focused_node: &mut Option<Node>,
-    patches: Vec<Patch<MSG>>,
-) -> Result<ActiveClosure, JsValue>
+    patches: Vec<P>,
+) -> Result<ActiveClosure, (ActiveClosure, DomError)>
 where
     N: Into<Node>,
     MSG: 'static,
     DSP: Clone + Dispatch<MSG> + 'static,
+    P: Into<DomPatch<'a, MSG>>,
 {
-    #[cfg(feature = "with-measure")]
-    let t1 = crate::now();
     let root_node: Node = root_node.into();
+    let patches: Vec<DomPatch<MSG>> =
+        patches.into_iter().map(Into::into).collect();
 
     #[cfg(feature = "with-nodeidx-debug")]
     log::trace!("patches: {:#?}", patches);
 
+    patch_nodes(
+        program,
+        root_node,
+        old_closures,
+        node_idx_lookup,
+        focused_node,
+        &patches,
+    )
+}
+
+/// apply the patches to the nodes under this root node, the moved nodes
+/// are patched recursively with the moved node as the root
+fn patch_nodes<DSP, MSG>(
+    program: Option<&DSP>,
+    root_node: Node,
+    old_closures: &mut ActiveClosure,
+    node_idx_lookup: &mut HashMap<NodeIdx, Node>,
+    focused_node: &mut Option<Node>,
+    patches: &[DomPatch<MSG>],
+) -> Result<ActiveClosure, (ActiveClosure, DomError)>
+where
+    MSG: 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    #[cfg(feature = "with-measure")]
+    let t1 = crate::now();
+
     // Closure that were added to the DOM during this patch operation.
     let mut active_closures = HashMap::new();
 
     // finding the nodes to be patched before hand, instead of calling it
     // in every patch loop.
-    let nodes_to_patch = find_nodes(root_node, node_idx_lookup, &patches);
+    let nodes_to_patch = find_nodes(root_node, node_idx_lookup, patches);
 
     #[cfg(feature = "with-nodeidx-debug")]
     log::trace!("nodes_to_patch: {:#?}", nodes_to_patch);
@@ -78,22 +115,16 @@ where
 
     //TODO: insert all patched_node idx only after everything has been applied
     for patch in patches.iter() {
-        let patch_node_idx = patch.node_idx();
-
-        if let Some(element) = nodes_to_patch.get(&patch_node_idx) {
-            let new_closures = apply_patch_to_node(
-                program,
-                &element,
-                old_closures,
-                node_idx_lookup,
-                focused_node,
-                &patch,
-            )?;
-            active_closures.extend(new_closures);
-        } else {
-            unreachable!(
-            "Getting here means we didn't find the element or next node that we were supposed to patch."
-            );
+        if let Err(error) = apply_dom_patch(
+            program,
+            &nodes_to_patch,
+            old_closures,
+            &mut active_closures,
+            node_idx_lookup,
+            focused_node,
+            patch,
+        ) {
+            return Err((active_closures, error));
         }
     }
 
@@ -107,6 +138,102 @@ where
     Ok(active_closures)
 }
 
+/// apply the patch to the node which is found in `nodes_to_patch`,
+/// the closures of the added event listeners are put into `active_closures`
+/// even when applying the patches of a moved node fails midway
+fn apply_dom_patch<DSP, MSG>(
+    program: Option<&DSP>,
+    nodes_to_patch: &HashMap<usize, Node>,
+    old_closures: &mut ActiveClosure,
+    active_closures: &mut ActiveClosure,
+    node_idx_lookup: &mut HashMap<NodeIdx, Node>,
+    focused_node: &mut Option<Node>,
+    patch: &DomPatch<MSG>,
+) -> Result<(), DomError>
+where
+    MSG: 'static,
+    DSP: Clone + Dispatch<MSG> + 'static,
+{
+    let patch_node_idx = patch.node_idx();
+
+    // the element or next node that we were supposed to patch is not found
+    let element = nodes_to_patch
+        .get(&patch_node_idx)
+        .ok_or(DomError::NodeNotFound(patch_node_idx))?;
+    match patch {
+        DomPatch::Patch(patch) => {
+            apply_patch_to_node(
+                program,
+                element,
+                old_closures,
+                node_idx_lookup,
+                focused_node,
+                active_closures,
+                patch,
+            )?;
+        }
+        DomPatch::MoveNode(move_node) => {
+            let target_idx = move_node.target.node_idx();
+            let target = nodes_to_patch
+                .get(&target_idx)
+                .ok_or(DomError::NodeNotFound(target_idx))?;
+            move_node_to_target(element, target, move_node.target)?;
+            let moved_closures = patch_nodes(
+                program,
+                element.clone(),
+                old_closures,
+                node_idx_lookup,
+                focused_node,
+                &move_node.patches,
+            );
+            match moved_closures {
+                Ok(moved_closures) => active_closures.extend(moved_closures),
+                Err((moved_closures, error)) => {
+                    active_closures.extend(moved_closures);
+                    return Err(error);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// relocate the node into the target, the node keeps its event listeners
+/// and its closures stays in the `ActiveClosure` as they are identified by the
+/// `data-sauron-vdom-id` of the node.
+/// The focus is restored if the focused element is inside the moved node.
+fn move_node_to_target(
+    node: &Node,
+    target: &Node,
+    move_target: MoveTarget,
+) -> Result<(), DomError> {
+    let focused_element = crate::dom::document()
+        .active_element()
+        .filter(|active| node.contains(Some(active)));
+
+    match move_target {
+        MoveTarget::InsertBefore(_) => {
+            let parent_node =
+                target.parent_node().ok_or(DomError::DetachedNode)?;
+            parent_node
+                .insert_before(node, Some(target))
+                .map_err(|e| DomError::modify_tree("move", e))?;
+        }
+        MoveTarget::AppendTo(_) => {
+            target
+                .append_child(node)
+                .map_err(|e| DomError::modify_tree("move", e))?;
+        }
+    }
+
+    match focused_element {
+        Some(focused_element) => {
+            CreatedNode::set_element_focus(&focused_element)
+        }
+        None => Ok(()),
+    }
+}
+
 /// find the nodes to be patched
 /// each patch contains a node index, arranged in depth first tree.
 ///
@@ -131,7 +258,7 @@ where
 fn find_nodes<MSG>(
     root_node: Node,
     node_idx_lookup: &HashMap<NodeIdx, Node>,
-    patches: &[Patch<MSG>],
+    patches: &[DomPatch<MSG>],
 ) -> HashMap<usize, Node> {
     let mut nodes_to_find = HashMap::new();
 
@@ -139,6 +266,10 @@ fn find_nodes<MSG>(
 
     for patch in patches {
         nodes_to_find.insert(patch.node_idx(), patch.tag());
+        // the node where the moved node is placed into
+        if let DomPatch::MoveNode(move_node) = patch {
+            nodes_to_find.entry(move_node.target.node_idx()).or_insert(None);
+        }
     }
 
     #[cfg(feature = "with-measure")]
@@ -174,6 +305,7 @@ fn find_nodes_recursive(
     // Important: We use child_nodes() instead of children() because children() ignores text nodes
     let children = node.child_nodes();
     let child_node_count = children.length();
+    let is_mounted_sub_component = is_sub_component(&node);
 
     // If the root node matches, mark it for patching
     if let Some(_vtag) = nodes_to_find.get(&cur_node_idx) {
@@ -211,8 +343,12 @@ fn find_nodes_recursive(
         nodes_to_patch.insert(*cur_node_idx, node);
     }
 
-    for i in 0..child_node_count {
-        let child_node = children.item(i).expect("Expecting a child node");
+    // the nodes of a sub component are not part of this view
+    if is_mounted_sub_component {
+        return false;
+    }
+
+    for child_node in (0..child_node_count).filter_map(|i| children.item(i)) {
         *cur_node_idx += 1;
         if find_nodes_recursive(
             child_node,
@@ -239,16 +375,20 @@ fn get_node_descendant_data_vdom_id(root_element: &Element) -> Vec<u32> {
     if let Some(vdom_id_str) =
         root_element.get_attribute(created_node::DATA_SAURON_VDOM_ID)
     {
-        let vdom_id = vdom_id_str
-            .parse::<u32>()
-            .expect("unable to parse sauron_vdom-id");
-        data_vdom_id.push(vdom_id);
+        match vdom_id_str.parse::<u32>() {
+            Ok(vdom_id) => data_vdom_id.push(vdom_id),
+            Err(_) => log::warn!("invalid sauron vdom-id: {}", vdom_id_str),
+        }
+    }
+
+    // the closures of a sub component are in its own program
+    if is_sub_component(root_element) {
+        return data_vdom_id;
     }
 
     let children = root_element.child_nodes();
     let child_node_count = children.length();
-    for i in 0..child_node_count {
-        let child_node = children.item(i).expect("Expecting a child node");
+    for child_node in (0..child_node_count).filter_map(|i| children.item(i)) {
         if let Node::ELEMENT_NODE = child_node.node_type() {
             let child_element = child_node.unchecked_ref::<Element>();
             let child_data_vdom_id =
@@ -259,23 +399,27 @@ fn get_node_descendant_data_vdom_id(root_element: &Element) -> Vec<u32> {
     data_vdom_id
 }
 
+/// true if this node is where a sub component is mounted
+fn is_sub_component(node: &Node) -> bool {
+    node.dyn_ref::<Element>()
+        .map(|element| element.has_attribute(DATA_SAURON_COMPONENT))
+        .unwrap_or(false)
+}
+
 /// remove all the event listeners for this node
 fn remove_event_listeners(
     node: &Element,
     old_closures: &mut ActiveClosure,
-) -> Result<(), JsValue> {
+) -> Result<(), DomError> {
     let all_descendant_vdom_id = get_node_descendant_data_vdom_id(node);
     for vdom_id in all_descendant_vdom_id {
-        if let Some(old_closure) = old_closures.get(&vdom_id) {
+        // remove closure active_closure in dom_updater to free up memory
+        if let Some(old_closure) = old_closures.remove(&vdom_id) {
             for (event, oc) in old_closure.iter() {
                 let func: &Function = oc.as_ref().unchecked_ref();
-                node.remove_event_listener_with_callback(event, func)?;
+                node.remove_event_listener_with_callback(event, func)
+                    .map_err(|e| DomError::event_listener("remove", event, e))?;
             }
-
-            // remove closure active_closure in dom_updater to free up memory
-            old_closures
-                .remove(&vdom_id)
-                .expect("Unable to remove old closure");
         } else {
             log::warn!(
                 "There is no closure marked with that vdom_id: {}",
@@ -286,19 +430,53 @@ fn remove_event_listeners(
     Ok(())
 }
 
+/// remove the event listeners of this element and all of its descendants,
+/// each listener is removed from the element where it is attached to, and
+/// its closure is removed from `closures`
+pub(crate) fn remove_all_event_listeners(
+    element: &Element,
+    closures: &mut ActiveClosure,
+) -> Result<(), DomError> {
+    let vdom_id = element
+        .get_attribute(created_node::DATA_SAURON_VDOM_ID)
+        .and_then(|vdom_id| vdom_id.parse::<u32>().ok());
+    if let Some(old_closure) = vdom_id.and_then(|id| closures.remove(&id)) {
+        for (event, oc) in old_closure.iter() {
+            let func: &Function = oc.as_ref().unchecked_ref();
+            element
+                .remove_event_listener_with_callback(event, func)
+                .map_err(|e| DomError::event_listener("remove", event, e))?;
+        }
+    }
+
+    // the closures of a sub component are in its own program
+    if is_sub_component(element) {
+        return Ok(());
+    }
+
+    let children = element.children();
+    for child in (0..children.length()).filter_map(|i| children.item(i)) {
+        remove_all_event_listeners(&child, closures)?;
+    }
+    Ok(())
+}
+
 /// remove the event listener which matches the given event name
 fn remove_event_listener_with_name(
     event_name: &'static str,
     node: &Element,
     old_closures: &mut ActiveClosure,
-) -> Result<(), JsValue> {
+) -> Result<(), DomError> {
     let all_descendant_vdom_id = get_node_descendant_data_vdom_id(node);
     for vdom_id in all_descendant_vdom_id {
         if let Some(old_closure) = old_closures.get_mut(&vdom_id) {
             for (event, oc) in old_closure.iter() {
                 if *event == event_name {
                     let func: &Function = oc.as_ref().unchecked_ref();
-                    node.remove_event_listener_with_callback(event, func)?;
+                    node.remove_event_listener_with_callback(event, func)
+                        .map_err(|e| {
+                            DomError::event_listener("remove", event, e)
+                        })?;
                 }
             }
 
@@ -306,9 +484,7 @@ fn remove_event_listener_with_name(
 
             // remove closure active_closure in dom_updater to free up memory
             if old_closure.is_empty() {
-                old_closures
-                    .remove(&vdom_id)
-                    .expect("Unable to remove old closure");
+                old_closures.remove(&vdom_id);
             }
         } else {
             log::warn!(
@@ -321,21 +497,21 @@ fn remove_event_listener_with_name(
 }
 
 /// apply a the patch to this element node.
-/// and return the ActiveClosure that may be attached to that element
+/// the closures that are attached to the element are put into
+/// `active_closures`, even when the patch fails midway
 fn apply_patch_to_node<DSP, MSG>(
     program: Option<&DSP>,
     node: &Node,
     old_closures: &mut ActiveClosure,
     node_idx_lookup: &mut HashMap<NodeIdx, Node>,
     focused_node: &mut Option<Node>,
+    active_closures: &mut ActiveClosure,
     patch: &Patch<MSG>,
-) -> Result<ActiveClosure, JsValue>
+) -> Result<(), DomError>
 where
     MSG: 'static,
     DSP: Clone + Dispatch<MSG> + 'static,
 {
-    let mut active_closures = ActiveClosure::new();
-
     match patch {
         Patch::InsertNode(InsertNode {
             tag: _,
@@ -350,14 +526,15 @@ where
                 &for_insert,
                 focused_node,
                 &mut Some(*new_node_idx),
-            );
+            )?;
             let parent_node =
-                element.parent_node().expect("must have a parent node");
+                element.parent_node().ok_or(DomError::DetachedNode)?;
             parent_node
                 .insert_before(&created_node.node, Some(element))
-                .expect("must remove target node");
+                .map_err(|e| DomError::modify_tree("insert", e))?;
 
-            Ok(active_closures)
+            active_closures.extend(created_node.closures);
+            Ok(())
         }
         Patch::AddAttributes(AddAttributes {
             #[cfg(feature = "with-nodeidx-debug")]
@@ -368,22 +545,22 @@ where
             let element: &Element = node.unchecked_ref();
             CreatedNode::set_element_attributes(
                 program,
-                &mut active_closures,
+                active_closures,
                 element,
                 attrs,
-            );
+            )?;
 
             #[cfg(feature = "with-nodeidx-debug")]
             CreatedNode::set_element_attributes(
                 program,
-                &mut active_closures,
+                active_closures,
                 element,
                 &[&crate::html::attributes::attr("node_idx", *new_node_idx)],
-            );
+            )?;
 
             #[cfg(feature = "with-nodeidx-debug")]
             node_idx_lookup.insert(*new_node_idx, node.clone());
-            Ok(active_closures)
+            Ok(())
         }
         Patch::RemoveAttributes(RemoveAttributes {
             #[cfg(feature = "with-nodeidx-debug")]
@@ -396,7 +573,13 @@ where
                 for att_value in attr.value() {
                     match att_value {
                         AttValue::Plain(_) => {
-                            element.remove_attribute(attr.name())?;
+                            element.remove_attribute(attr.name()).map_err(
+                                |e| DomError::RemoveAttribute {
+                                    name: attr.name().to_string(),
+                                    message:
+                                        crate::dom::util::js_error_message(e),
+                                },
+                            )?;
                         }
                         // it is an event listener
                         AttValue::Callback(_) => {
@@ -413,15 +596,15 @@ where
             #[cfg(feature = "with-nodeidx-debug")]
             CreatedNode::set_element_attributes(
                 program,
-                &mut active_closures,
+                active_closures,
                 element,
                 &[&crate::html::attributes::attr("node_idx", *new_node_idx)],
-            );
+            )?;
 
             #[cfg(feature = "with-nodeidx-debug")]
             node_idx_lookup.insert(*new_node_idx, node.clone());
 
-            Ok(active_closures)
+            Ok(())
         }
 
         // This also removes the associated closures and event listeners to the node being replaced
@@ -441,13 +624,16 @@ where
                 replacement,
                 focused_node,
                 &mut Some(*new_node_idx),
-            );
+            )?;
             if element.node_type() != Node::TEXT_NODE {
                 remove_event_listeners(&element, old_closures)?;
             }
-            element.replace_with_with_node_1(&created_node.node)?;
+            element
+                .replace_with_with_node_1(&created_node.node)
+                .map_err(|e| DomError::modify_tree("replace", e))?;
 
-            Ok(created_node.closures)
+            active_closures.extend(created_node.closures);
+            Ok(())
         }
         Patch::RemoveNode(RemoveNode {
             #[cfg(feature = "with-nodeidx-debug")]
@@ -456,13 +642,13 @@ where
         }) => {
             let element: &Element = node.unchecked_ref();
             let parent_node =
-                element.parent_node().expect("must have a parent node");
+                element.parent_node().ok_or(DomError::DetachedNode)?;
             if element.node_type() == Node::COMMENT_NODE {
                 //do not remove comment nodes
             } else {
                 parent_node
                     .remove_child(element)
-                    .expect("must remove target node");
+                    .map_err(|e| DomError::modify_tree("remove", e))?;
                 if element.node_type() != Node::TEXT_NODE {
                     let element: &Element = node.unchecked_ref();
                     remove_event_listeners(&element, old_closures)?;
@@ -478,7 +664,7 @@ where
                     log::error!("no node_idx to remove");
                 }
             }
-            Ok(active_closures)
+            Ok(())
         }
         Patch::AppendChildren(AppendChildren {
             tag: _,
@@ -486,7 +672,8 @@ where
             children: new_nodes,
         }) => {
             let element: &Element = node.unchecked_ref();
-            let mut active_closures = HashMap::new();
+            // the closures of the children which are already appended are
+            // kept, even if appending the next child fails
             for (append_children_node_idx, new_node) in new_nodes.iter() {
                 let created_node = CreatedNode::create_dom_node_opt::<DSP, MSG>(
                     program,
@@ -494,17 +681,19 @@ where
                     &new_node,
                     focused_node,
                     &mut Some(*append_children_node_idx),
-                );
-                element.append_child(&created_node.node)?;
+                )?;
+                element
+                    .append_child(&created_node.node)
+                    .map_err(|e| DomError::modify_tree("append", e))?;
                 active_closures.extend(created_node.closures);
             }
-            Ok(active_closures)
+            Ok(())
         }
         Patch::ChangeText(ct) => {
             node.set_node_value(Some(&ct.new.text));
             #[cfg(feature = "with-nodeidx-debug")]
             node_idx_lookup.insert(ct.new_node_idx, node.clone());
-            Ok(active_closures)
+            Ok(())
         }
     }
 }
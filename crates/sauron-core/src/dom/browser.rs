@@ -1,7 +1,11 @@
-use crate::{Cmd, Component, Dispatch};
-use std::fmt::Debug;
-use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::ScrollToOptions;
+use crate::{
+    dom::{subscriptions::EventListener, util::js_error_message, DomError},
+    sub::{CustomSubscription, Subscription},
+    Cmd, Component, Dispatch, Sub,
+};
+use std::{any::Any, fmt::Debug, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsValue};
+use web_sys::{PopStateEvent, ScrollToOptions};
 
 /// provides an interface for doing url request, such as fetch
 /// resize events, keyboard event, timeout event
@@ -9,51 +13,59 @@ use web_sys::ScrollToOptions;
 pub struct Browser;
 
 impl Browser {
-    /// Creates a Cmd in which the MSG will be emitted
-    /// whenever the browser is resized
-    pub fn on_resize<F, APP, MSG>(cb: F) -> Cmd<APP, MSG>
+    /// Creates a subscription which emits the msg returned from `cb` with
+    /// the width and height of the window whenever the browser is resized.
+    /// The listener is removed when it is no longer in the subscriptions.
+    pub fn on_resize<F, MSG>(cb: F) -> Sub<MSG>
     where
-        F: Fn(i32, i32) -> MSG + Clone + 'static,
+        F: Fn(i32, i32) -> MSG + 'static,
         MSG: 'static,
-        APP: Component<MSG> + 'static,
     {
-        let cmd: Cmd<APP, MSG> = Cmd::new(move |program| {
-            let cb_clone = cb.clone();
-            let resize_callback: Closure<dyn Fn(web_sys::Event)> =
-                Closure::wrap(Box::new(move |_| {
-                    let (window_width, window_height) = Self::get_size();
-                    let msg = cb_clone(window_width, window_height);
-                    program.dispatch(msg);
-                }));
-            crate::window()
-                .set_onresize(Some(resize_callback.as_ref().unchecked_ref()));
-            resize_callback.forget();
-        });
-        cmd
+        Self::on_window_event("resize", move || {
+            let (window_width, window_height) = Self::get_size();
+            cb(window_width, window_height)
+        })
     }
 
-    /// attached a callback and will be triggered when the hash portion of the window location
-    /// url is changed
-    pub fn on_hashchange<F, APP, MSG>(cb: F) -> Cmd<APP, MSG>
+    /// Creates a subscription which emits the msg returned from `cb` with
+    /// the hash of the url whenever the hash portion of the window location
+    /// url is changed.
+    /// The listener is removed when it is no longer in the subscriptions.
+    pub fn on_hashchange<F, MSG>(cb: F) -> Sub<MSG>
     where
-        F: Fn(String) -> MSG + Clone + 'static,
+        F: Fn(String) -> MSG + 'static,
+        MSG: 'static,
+    {
+        Self::on_window_event("hashchange", move || cb(Self::get_hash()))
+    }
+
+    /// a subscription to the event of the window which emits the msg
+    /// returned from `f`
+    fn on_window_event<F, MSG>(event_name: &'static str, f: F) -> Sub<MSG>
+    where
+        F: Fn() -> MSG + 'static,
         MSG: 'static,
-        APP: Component<MSG> + 'static,
     {
-        let cmd: Cmd<APP, MSG> = Cmd::new(move |program| {
-            let cb_clone = cb.clone();
-            let hashchange_callback: Closure<dyn Fn(web_sys::Event)> =
-                Closure::wrap(Box::new(move |_| {
-                    let hash = Self::get_hash();
-                    let msg = cb_clone(hash);
-                    program.dispatch(msg);
-                }));
-            crate::window().set_onhashchange(Some(
-                hashchange_callback.as_ref().unchecked_ref(),
-            ));
-            hashchange_callback.forget();
-        });
-        cmd
+        let f = Rc::new(f);
+        let subscription = CustomSubscription::new(
+            format!("browser:{}", event_name),
+            move |program: Rc<dyn Dispatch<MSG>>| {
+                let f = Rc::clone(&f);
+                let program_clone = Rc::clone(&program);
+                let closure: Closure<dyn FnMut(web_sys::Event)> =
+                    Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                        program_clone.dispatch(f());
+                    }));
+                let listener: Box<dyn Any> = Box::new(EventListener::attach(
+                    &*program,
+                    crate::window().into(),
+                    event_name,
+                    closure,
+                ));
+                listener
+            },
+        );
+        Sub::from(Subscription::Custom(subscription))
     }
 
     /// return the size of the browser at this moment
@@ -80,6 +92,74 @@ impl Browser {
         hash
     }
 
+    /// return the current url of the browser without the origin,
+    /// which is the path, the query and the hash, ie: `/users/1?tab=posts`
+    pub fn get_url() -> String {
+        let location = crate::window().location();
+        format!(
+            "{}{}{}",
+            location.pathname().expect("must have a pathname"),
+            location.search().expect("must have a search"),
+            location.hash().expect("must have a hash"),
+        )
+    }
+
+    /// Creates a Cmd which adds a new history entry with this url,
+    /// the url change subscriptions are then notified.
+    /// The url can be a `&str` or an `Url` with encoded segments and query.
+    /// The url which can not be navigated to, such as an url of another
+    /// origin, is reported to the error hook of the `Program`.
+    pub fn push_url<APP, MSG>(url: impl ToString) -> Cmd<APP, MSG>
+    where
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        let url = url.to_string();
+        Cmd::new(move |program| {
+            if let Err(error) = Self::navigate(&url) {
+                program.report_error(error);
+            }
+        })
+    }
+
+    /// Creates a Cmd which replaces the current history entry with this url,
+    /// the url change subscriptions are then notified.
+    /// The url which can not be navigated to is reported to the error hook
+    /// of the `Program`.
+    pub fn replace_url<APP, MSG>(url: impl ToString) -> Cmd<APP, MSG>
+    where
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        let url = url.to_string();
+        Cmd::new(move |program| {
+            let result = crate::history()
+                .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+                .and_then(|_| Self::notify_url_change())
+                .map_err(|e| navigate_error(&url, e));
+            if let Err(error) = result {
+                program.report_error(error);
+            }
+        })
+    }
+
+    /// add a new history entry with this url and notify the subscriptions
+    pub(crate) fn navigate(url: &str) -> Result<(), DomError> {
+        crate::history()
+            .push_state_with_url(&JsValue::NULL, "", Some(url))
+            .and_then(|_| Self::notify_url_change())
+            .map_err(|e| navigate_error(url, e))
+    }
+
+    /// The browser only triggers `popstate` on back and forward navigation,
+    /// so it is triggered here for the url change subscriptions to be
+    /// notified for the urls which are changed by the app.
+    fn notify_url_change() -> Result<(), JsValue> {
+        let event = PopStateEvent::new("popstate")?;
+        crate::window().dispatch_event(&event)?;
+        Ok(())
+    }
+
     /// scroll the browser to the top of the document
     pub fn scroll_to_top() {
         let mut options = ScrollToOptions::new();
@@ -88,3 +168,10 @@ impl Browser {
         crate::window().scroll_to_with_scroll_to_options(&options);
     }
 }
+
+fn navigate_error(url: &str, error: JsValue) -> DomError {
+    DomError::Navigate {
+        url: url.to_string(),
+        message: js_error_message(error),
+    }
+}
@@ -1,16 +1,16 @@
-use crate::events::MountEvent;
+use crate::events::{Listener, MountEvent};
 use crate::{
-    dom::Dispatch,
+    dom::{sub_component, DomError},
+    Dispatch,
     html,
-    html::attributes::Special,
-    mt_dom::{Callback, NodeIdx},
+    html::{attributes::Special, MEMO},
+    mt_dom::NodeIdx,
     Attribute,
 };
-use mt_dom::AttValue;
-use std::{collections::HashMap, sync::Mutex};
-use wasm_bindgen::{closure::Closure, JsCast};
+use std::{collections::HashMap, rc::Rc, sync::Mutex};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use web_sys::{
-    self, Element, Event, EventTarget, HtmlElement, HtmlInputElement,
+    self, Element, EventTarget, HtmlElement, HtmlInputElement,
     HtmlTextAreaElement, Node, Text,
 };
 
@@ -73,7 +73,7 @@ impl CreatedNode {
         vnode: &crate::Node<MSG>,
         focused_node: &mut Option<Node>,
         node_idx: &mut Option<NodeIdx>,
-    ) -> CreatedNode
+    ) -> Result<CreatedNode, DomError>
     where
         MSG: 'static,
         DSP: Clone + Dispatch<MSG> + 'static,
@@ -100,7 +100,7 @@ impl CreatedNode {
         vnode: &crate::Node<MSG>,
         focused_node: &mut Option<Node>,
         node_idx: &mut Option<NodeIdx>,
-    ) -> CreatedNode
+    ) -> Result<CreatedNode, DomError>
     where
         MSG: 'static,
         DSP: Clone + Dispatch<MSG> + 'static,
@@ -113,19 +113,15 @@ impl CreatedNode {
                     node_idx_lookup
                         .insert(*node_idx, text_node.clone().unchecked_into());
                 }
-                CreatedNode::without_closures(text_node.unchecked_into())
-            }
-            crate::Node::Element(element_node) => {
-                let created_element: CreatedNode = Self::create_element_node(
-                    program,
-                    node_idx_lookup,
-                    element_node,
-                    focused_node,
-                    node_idx,
-                )
-                .into();
-                created_element
+                Ok(CreatedNode::without_closures(text_node.unchecked_into()))
             }
+            crate::Node::Element(element_node) => Self::create_element_node(
+                program,
+                node_idx_lookup,
+                element_node,
+                focused_node,
+                node_idx,
+            ),
         }
     }
 
@@ -139,16 +135,12 @@ impl CreatedNode {
         for att in velem.attrs.iter() {
             if *att.name() == "mount" {
                 log::trace!("found a mount event");
-                for val in att.value().iter() {
-                    match val {
-                        AttValue::Callback(cb) => {
-                            cb.emit(MountEvent {
-                                target_node: element.clone().unchecked_into(),
-                            });
-                        }
-                        AttValue::Plain(_) => {
-                            log::warn!("mount should not be a plain value");
-                        }
+                for listener in Listener::of_attribute(att) {
+                    let mount_event = MountEvent {
+                        target_node: element.clone().unchecked_into(),
+                    };
+                    if let Err(error) = listener.emit(mount_event.into()) {
+                        log::error!("{}", error);
                     }
                 }
             }
@@ -163,22 +155,22 @@ impl CreatedNode {
         velem: &crate::Element<MSG>,
         focused_node: &mut Option<Node>,
         node_idx: &mut Option<NodeIdx>,
-    ) -> CreatedNode
+    ) -> Result<CreatedNode, DomError>
     where
         MSG: 'static,
         DSP: Clone + Dispatch<MSG> + 'static,
     {
         let document = crate::document();
 
-        let element = if let Some(ref namespace) = velem.namespace() {
-            document
-                .create_element_ns(Some(namespace), &velem.tag())
-                .expect("Unable to create element")
+        let element = if let Some(namespace) = velem.namespace() {
+            document.create_element_ns(Some(namespace), velem.tag())
         } else {
-            document
-                .create_element(&velem.tag())
-                .expect("Unable to create element")
-        };
+            document.create_element(velem.tag())
+        }
+        .map_err(|e| DomError::CreateElement {
+            tag: velem.tag().to_string(),
+            message: crate::dom::util::js_error_message(e),
+        })?;
 
         Self::dispatch_mount_event(velem, &element);
 
@@ -199,7 +191,7 @@ impl CreatedNode {
             &mut closures,
             &element,
             &velem.get_attributes().iter().collect::<Vec<_>>(),
-        );
+        )?;
 
         #[cfg(feature = "with-nodeidx-debug")]
         if let Some(node_idx) = node_idx {
@@ -208,7 +200,7 @@ impl CreatedNode {
                 &mut closures,
                 &element,
                 &[&crate::prelude::attr("node_idx", *node_idx)],
-            );
+            )?;
         }
 
         let mut previous_node_was_text = false;
@@ -229,12 +221,12 @@ impl CreatedNode {
                         let separator = document.create_comment("mordor");
                         current_node
                             .append_child(separator.as_ref())
-                            .expect("Unable to append child");
+                            .map_err(|e| DomError::modify_tree("append", e))?;
                     }
                     let text_node = Self::create_text_node(&txt.text);
                     current_node
                         .append_child(&text_node)
-                        .expect("Unable to append text node");
+                        .map_err(|e| DomError::modify_tree("append", e))?;
 
                     #[cfg(feature = "with-nodeidx-debug")]
                     if let Some(node_idx) = &node_idx {
@@ -253,18 +245,324 @@ impl CreatedNode {
                         child,
                         focused_node,
                         node_idx,
-                    );
+                    )?;
                     closures.extend(created_child.closures);
 
                     element
                         .append_child(&created_child.node)
-                        .expect("Unable to append element node");
+                        .map_err(|e| DomError::modify_tree("append", e))?;
                 }
             }
         }
 
+        Self::mount_sub_component(program, velem, &element);
+
         let node: Node = element.unchecked_into();
-        CreatedNode { node, closures }
+        Ok(CreatedNode { node, closures })
+    }
+
+    /// mount the sub component, if this element is where it is placed
+    fn mount_sub_component<DSP, MSG>(
+        program: Option<&DSP>,
+        velem: &crate::Element<MSG>,
+        element: &Element,
+    ) where
+        MSG: 'static,
+        DSP: Clone + Dispatch<MSG> + 'static,
+    {
+        if velem
+            .get_attribute_value(&sub_component::DATA_SAURON_COMPONENT)
+            .is_some()
+        {
+            let parent = program.map(|program| {
+                let parent: Rc<dyn Dispatch<MSG>> = Rc::new(program.clone());
+                parent
+            });
+            sub_component::mount_sub_component(element, parent);
+        }
+    }
+
+    /// Hydrate the existing `dom_node`, which was rendered in the server from the same view.
+    /// The event listeners of the vnode are attached to the existing DOM nodes
+    /// instead of creating the DOM nodes all over again.
+    ///
+    /// Mismatches between the existing DOM and the vnode are logged and repaired,
+    /// by creating the expected node in place of the mismatched one.
+    pub fn hydrate_dom_node<DSP, MSG>(
+        program: &DSP,
+        node_idx_lookup: &mut HashMap<NodeIdx, Node>,
+        vnode: &crate::Node<MSG>,
+        dom_node: &Node,
+        focused_node: &mut Option<Node>,
+        node_idx: &mut Option<NodeIdx>,
+    ) -> Result<CreatedNode, DomError>
+    where
+        MSG: 'static,
+        DSP: Clone + Dispatch<MSG> + 'static,
+    {
+        match vnode {
+            crate::Node::Text(txt) => {
+                if dom_node.node_type() != Node::TEXT_NODE {
+                    return Self::replace_mismatched_node(
+                        program,
+                        node_idx_lookup,
+                        vnode,
+                        dom_node,
+                        focused_node,
+                        node_idx,
+                    );
+                }
+                if dom_node.node_value().as_deref() != Some(&txt.text) {
+                    log::warn!(
+                        "hydration mismatch: expecting text {:?}, found: {:?}",
+                        txt.text,
+                        dom_node.node_value()
+                    );
+                    dom_node.set_node_value(Some(&txt.text));
+                }
+                #[cfg(feature = "with-nodeidx-debug")]
+                if let Some(node_idx) = node_idx {
+                    node_idx_lookup.insert(*node_idx, dom_node.clone());
+                }
+                Ok(CreatedNode::without_closures(dom_node.clone()))
+            }
+            crate::Node::Element(velem) => {
+                let is_same_tag = dom_node.node_type() == Node::ELEMENT_NODE
+                    && dom_node.node_name().eq_ignore_ascii_case(velem.tag());
+                if is_same_tag {
+                    Self::hydrate_element_node(
+                        program,
+                        node_idx_lookup,
+                        velem,
+                        dom_node.unchecked_ref(),
+                        focused_node,
+                        node_idx,
+                    )
+                } else {
+                    Self::replace_mismatched_node(
+                        program,
+                        node_idx_lookup,
+                        vnode,
+                        dom_node,
+                        focused_node,
+                        node_idx,
+                    )
+                }
+            }
+        }
+    }
+
+    /// create the node for this vnode and replace the mismatched dom_node with it
+    fn replace_mismatched_node<DSP, MSG>(
+        program: &DSP,
+        node_idx_lookup: &mut HashMap<NodeIdx, Node>,
+        vnode: &crate::Node<MSG>,
+        dom_node: &Node,
+        focused_node: &mut Option<Node>,
+        node_idx: &mut Option<NodeIdx>,
+    ) -> Result<CreatedNode, DomError>
+    where
+        MSG: 'static,
+        DSP: Clone + Dispatch<MSG> + 'static,
+    {
+        log::warn!(
+            "hydration mismatch: expecting {:?}, found: {:?}",
+            vnode.tag().unwrap_or(&"#text"),
+            dom_node.node_name()
+        );
+        let created_node = Self::create_dom_node(
+            program,
+            node_idx_lookup,
+            vnode,
+            focused_node,
+            node_idx,
+        )?;
+        if let Some(parent_node) = dom_node.parent_node() {
+            parent_node
+                .replace_child(&created_node.node, dom_node)
+                .map_err(|e| DomError::modify_tree("replace", e))?;
+        }
+        Ok(created_node)
+    }
+
+    /// Hydrate the element and its children, the children that are not in the view are removed
+    /// while the missing ones are created.
+    fn hydrate_element_node<DSP, MSG>(
+        program: &DSP,
+        node_idx_lookup: &mut HashMap<NodeIdx, Node>,
+        velem: &crate::Element<MSG>,
+        element: &Element,
+        focused_node: &mut Option<Node>,
+        node_idx: &mut Option<NodeIdx>,
+    ) -> Result<CreatedNode, DomError>
+    where
+        MSG: 'static,
+        DSP: Clone + Dispatch<MSG> + 'static,
+    {
+        let document = crate::document();
+
+        Self::dispatch_mount_event(velem, element);
+
+        if velem.is_focused() {
+            *focused_node = Some(element.clone().unchecked_into());
+            log::trace!("element is focused..{:?}", focused_node);
+        }
+
+        #[cfg(feature = "with-nodeidx-debug")]
+        if let Some(ref node_idx) = node_idx {
+            node_idx_lookup.insert(*node_idx, element.clone().unchecked_into());
+        }
+
+        let mut closures = ActiveClosure::new();
+
+        let child_nodes = element.child_nodes();
+        let mut dom_children = (0..child_nodes.length())
+            .filter_map(|i| child_nodes.item(i))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .peekable();
+
+        let is_blank_text = |node: &Node| {
+            node.node_type() == Node::TEXT_NODE
+                && node
+                    .node_value()
+                    .map(|v| v.trim().is_empty())
+                    .unwrap_or(true)
+        };
+
+        let mut previous_node_was_text = false;
+
+        for child in velem.get_children().iter() {
+            node_idx.as_mut().map(|node_idx| *node_idx += 1);
+            let next_dom_child = match child {
+                crate::Node::Text(_) => {
+                    // adjacent text nodes from the server are merged into one by the browser,
+                    // so we put back the separator the same way `create_element_node` does
+                    if previous_node_was_text {
+                        if dom_children.peek().map(|n| n.node_type())
+                            == Some(Node::COMMENT_NODE)
+                        {
+                            dom_children.next();
+                        } else {
+                            let separator = document.create_comment("mordor");
+                            element
+                                .insert_before(
+                                    separator.as_ref(),
+                                    dom_children.peek(),
+                                )
+                                .map_err(|e| {
+                                    DomError::modify_tree("insert", e)
+                                })?;
+                        }
+                    }
+                    previous_node_was_text = true;
+                    dom_children.next_if(|n| n.node_type() == Node::TEXT_NODE)
+                }
+                crate::Node::Element(_) => {
+                    previous_node_was_text = false;
+                    // the whitespace in between elements from the indented `Render::render`
+                    // is not part of the view
+                    while let Some(blank) = dom_children.next_if(is_blank_text)
+                    {
+                        element
+                            .remove_child(&blank)
+                            .map_err(|e| DomError::modify_tree("remove", e))?;
+                    }
+                    dom_children.next()
+                }
+            };
+
+            if let Some(dom_child) = next_dom_child {
+                let hydrated_child = Self::hydrate_dom_node(
+                    program,
+                    node_idx_lookup,
+                    child,
+                    &dom_child,
+                    focused_node,
+                    node_idx,
+                )?;
+                closures.extend(hydrated_child.closures);
+            } else {
+                log::warn!(
+                    "hydration mismatch: missing {:?} in {:?}",
+                    child.tag().unwrap_or(&"#text"),
+                    velem.tag()
+                );
+                let created_child = Self::create_dom_node(
+                    program,
+                    node_idx_lookup,
+                    child,
+                    focused_node,
+                    node_idx,
+                )?;
+                element
+                    .insert_before(&created_child.node, dom_children.peek())
+                    .map_err(|e| DomError::modify_tree("insert", e))?;
+                closures.extend(created_child.closures);
+            }
+        }
+
+        // the content of inner_html is not part of the view
+        if velem.get_attribute_value(&"inner_html").is_none() {
+            for extra_node in dom_children {
+                log::warn!(
+                    "hydration mismatch: removing {:?} which is not in the view",
+                    extra_node.node_name()
+                );
+                element
+                    .remove_child(&extra_node)
+                    .map_err(|e| DomError::modify_tree("remove", e))?;
+            }
+        }
+
+        Self::remove_stale_attributes(velem, element)?;
+        Self::set_element_attributes(
+            Some(program),
+            &mut closures,
+            element,
+            &velem.get_attributes().iter().collect::<Vec<_>>(),
+        )?;
+
+        Self::mount_sub_component(Some(program), velem, element);
+
+        Ok(CreatedNode {
+            node: element.clone().unchecked_into(),
+            closures,
+        })
+    }
+
+    /// remove the attributes of the server rendered element which are not in
+    /// the view, the `data-sauron-*` attributes are kept since they are set
+    /// by sauron itself
+    fn remove_stale_attributes<MSG>(
+        velem: &crate::Element<MSG>,
+        element: &Element,
+    ) -> Result<(), DomError> {
+        let names: Vec<&str> = velem
+            .get_attributes()
+            .iter()
+            .map(|att| *att.name())
+            .filter(|name| *name != MEMO)
+            .collect();
+        let dom_names = element.get_attribute_names();
+        for dom_name in dom_names.iter().filter_map(|name| name.as_string()) {
+            let in_view =
+                names.iter().any(|name| name.eq_ignore_ascii_case(&dom_name));
+            if in_view || dom_name.starts_with("data-sauron-") {
+                continue;
+            }
+            log::warn!(
+                "hydration mismatch: removing the attribute {:?} which is not in the view",
+                dom_name
+            );
+            element.remove_attribute(&dom_name).map_err(|e| {
+                DomError::RemoveAttribute {
+                    name: dom_name.to_string(),
+                    message: crate::dom::util::js_error_message(e),
+                }
+            })?;
+        }
+        Ok(())
     }
 
     /// set the element attribute
@@ -273,14 +571,22 @@ impl CreatedNode {
         closures: &mut ActiveClosure,
         element: &Element,
         attrs: &[&Attribute<MSG>],
-    ) where
+    ) -> Result<(), DomError>
+    where
         MSG: 'static,
         DSP: Clone + Dispatch<MSG> + 'static,
     {
-        let attrs = mt_dom::merge_attributes_of_same_name(attrs);
+        // the memo of a lazy node is only used in diffing
+        let attrs: Vec<&Attribute<MSG>> = attrs
+            .iter()
+            .copied()
+            .filter(|att| *att.name() != MEMO)
+            .collect();
+        let attrs = mt_dom::merge_attributes_of_same_name(&attrs);
         for att in attrs {
-            Self::set_element_attribute(program, closures, element, &att);
+            Self::set_element_attribute(program, closures, element, &att)?;
         }
+        Ok(())
     }
 
     /// set the element attribute
@@ -289,11 +595,16 @@ impl CreatedNode {
         closures: &mut ActiveClosure,
         element: &Element,
         attr: &Attribute<MSG>,
-    ) where
+    ) -> Result<(), DomError>
+    where
         MSG: 'static,
         DSP: Clone + Dispatch<MSG> + 'static,
     {
-        let (callbacks, plain_values, func_values) =
+        let set_attribute_error = |e: JsValue| DomError::SetAttribute {
+            name: attr.name().to_string(),
+            message: crate::dom::util::js_error_message(e),
+        };
+        let (_callbacks, plain_values, func_values) =
             html::attributes::partition_callbacks_from_plain_and_func_calls(
                 attr,
             );
@@ -313,12 +624,7 @@ impl CreatedNode {
                         attr.name(),
                         &merged_plain_values,
                     )
-                    .unwrap_or_else(|_| {
-                        panic!(
-                            "Error setting an attribute_ns for {:?}",
-                            element
-                        )
-                    });
+                    .map_err(set_attribute_error)?;
             } else {
                 match *attr.name() {
                     "value" => {
@@ -352,21 +658,19 @@ impl CreatedNode {
                     _ => {
                         element
                             .set_attribute(attr.name(), &merged_plain_values)
-                            .unwrap_or_else(|_| {
-                                panic!(
-                                    "Error setting an attribute for {:?}",
-                                    element
-                                )
-                            });
+                            .map_err(set_attribute_error)?;
                     }
                 }
             }
         } else {
             //if the merged attribute is blank of empty when string is trimmed
             //remove the attribute
-            element
-                .remove_attribute(attr.name())
-                .expect("must remove attribute");
+            element.remove_attribute(attr.name()).map_err(|e| {
+                DomError::RemoveAttribute {
+                    name: attr.name().to_string(),
+                    message: crate::dom::util::js_error_message(e),
+                }
+            })?;
         }
 
         // do function calls such as set_inner_html
@@ -380,37 +684,38 @@ impl CreatedNode {
         }
 
         // add callbacks using add_event_listener
-        for callback in callbacks {
+        for listener in Listener::of_attribute(attr) {
             let unique_id = create_unique_identifier();
 
             // set the data-sauron_vdom-id this will be read later on
             // when it's time to remove this element and its closures and event listeners
             element
                 .set_attribute(DATA_SAURON_VDOM_ID, &unique_id.to_string())
-                .expect("Could not set attribute on element");
+                .map_err(set_attribute_error)?;
 
             closures.insert(unique_id, vec![]);
 
             if let Some(program) = program {
                 let event_str = attr.name();
-                let current_elm: &EventTarget =
-                    element.dyn_ref().expect("unable to cast to event targe");
+                let current_elm: &EventTarget = element.as_ref();
 
                 // a custom enter event which triggers the callback
                 // when the enter key is pressed
                 if *event_str == "enter" {
                     let program_clone = program.clone();
-                    let callback_clone = callback.clone();
                     let key_press_func: Closure<dyn FnMut(web_sys::Event)> =
                         Closure::wrap(Box::new(
                             move |event: web_sys::Event| {
-                                let ke: &web_sys::KeyboardEvent = event
-                                    .dyn_ref()
-                                    .expect("should be a keyboard event");
-                                if ke.key() == "Enter" {
-                                    let msg = callback_clone
-                                        .emit(Into::<Event>::into(event));
-                                    program_clone.dispatch(msg);
+                                let is_enter = event
+                                    .dyn_ref::<web_sys::KeyboardEvent>()
+                                    .map(|ke| ke.key() == "Enter")
+                                    .unwrap_or(false);
+                                if is_enter {
+                                    emit_event(
+                                        &program_clone,
+                                        &listener,
+                                        event,
+                                    );
                                 }
                             },
                         ));
@@ -420,44 +725,55 @@ impl CreatedNode {
                             "keypress",
                             key_press_func.as_ref().unchecked_ref(),
                         )
-                        .expect("unable to attach enter event listener");
+                        .map_err(|e| {
+                            DomError::event_listener("add", "keypress", e)
+                        })?;
 
                     key_press_func.forget();
                 } else {
                     let closure_wrap: Closure<dyn FnMut(web_sys::Event)> =
-                        create_closure_wrap(program, &callback);
+                        create_closure_wrap(program, listener);
                     current_elm
                         .add_event_listener_with_callback(
                             event_str,
                             closure_wrap.as_ref().unchecked_ref(),
                         )
-                        .expect("Unable to attached event listener");
+                        .map_err(|e| {
+                            DomError::event_listener("add", event_str, e)
+                        })?;
                     closures
-                        .get_mut(&unique_id)
-                        .expect("Unable to get closure")
+                        .entry(unique_id)
+                        .or_default()
                         .push((event_str, closure_wrap));
                 }
             }
         }
+        Ok(())
     }
 
     /// set focus to this element
-    pub(crate) fn set_element_focus(element: &Element) {
-        let html_element: &HtmlElement = element.unchecked_ref();
-        html_element.focus().expect("must focus")
+    pub(crate) fn set_element_focus(element: &Element) -> Result<(), DomError> {
+        match element.dyn_ref::<HtmlElement>() {
+            Some(html_element) => html_element.focus().map_err(|e| {
+                DomError::Focus(crate::dom::util::js_error_message(e))
+            }),
+            None => Err(DomError::Focus(format!(
+                "{} is not an html element",
+                element.tag_name()
+            ))),
+        }
     }
 }
 
 /// This wrap into a closure the function that is dispatched when the event is triggered.
 pub(crate) fn create_closure_wrap<DSP, MSG>(
     program: &DSP,
-    callback: &Callback<crate::Event, MSG>,
+    listener: Listener<MSG>,
 ) -> Closure<dyn FnMut(web_sys::Event)>
 where
     MSG: 'static,
     DSP: Clone + Dispatch<MSG> + 'static,
 {
-    let callback_clone = callback.clone();
     // TODO: use a weak pointer here
     // let program_weak = Rc::downgrade(&program)
     let program_clone = program.clone();
@@ -476,7 +792,23 @@ where
         // - calling event.prevent_default() prevent InputEvent to trigger when KeyPressEvent is
         // also one of the event callback
         // event.prevent_default();
-        let msg = callback_clone.emit(event);
-        program_clone.dispatch(msg);
+        emit_event(&program_clone, &listener, event);
     }))
 }
+
+/// map the event into a msg using the listener, then dispatch it.
+/// If the event can not be mapped, ie: the event target is not the expected
+/// element, the callback is not called and the error is reported instead
+fn emit_event<DSP, MSG>(
+    program: &DSP,
+    listener: &Listener<MSG>,
+    event: web_sys::Event,
+) where
+    MSG: 'static,
+    DSP: Dispatch<MSG>,
+{
+    match listener.emit(crate::Event::from(event)) {
+        Ok(msg) => program.dispatch(msg),
+        Err(error) => program.report_error(error),
+    }
+}
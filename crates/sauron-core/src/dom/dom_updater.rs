@@ -1,12 +1,16 @@
 use crate::{
-    diff,
+    diff_with_moves,
     dom::{
-        apply_patches::patch,
+        apply_patches::{
+            patch,
+            remove_all_event_listeners,
+        },
         created_node::{
             ActiveClosure,
             CreatedNode,
         },
         Dispatch,
+        DomError,
     },
     mt_dom::NodeIdx,
     Patch,
@@ -69,16 +73,23 @@ where
     /// Mount the current_vdom appending to the actual browser DOM specified in the root_node
     /// This also gets the closures that was created when mounting the vdom to their
     /// actual DOM counterparts.
-    pub fn append_to_mount<DSP>(&mut self, program: &DSP)
+    pub fn append_to_mount<DSP>(
+        &mut self,
+        program: &DSP,
+    ) -> Result<(), DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
-        self.mount(program, false);
+        self.mount(program, false)
     }
 
     /// each element and it's descendant in the vdom is created into
     /// an actual DOM node.
-    fn mount<DSP>(&mut self, program: &DSP, replace: bool)
+    fn mount<DSP>(
+        &mut self,
+        program: &DSP,
+        replace: bool,
+    ) -> Result<(), DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
@@ -88,40 +99,62 @@ where
             &self.current_vdom,
             &mut self.focused_node,
             &mut None,
-        );
+        )?;
         if replace {
             let root_element: &Element = self.root_node.unchecked_ref();
             root_element
                 .replace_with_with_node_1(&created_node.node)
-                .expect("Could not append child to mount");
+                .map_err(|e| DomError::modify_tree("replace", e))?;
         } else {
             self.root_node
                 .append_child(&created_node.node)
-                .expect("Could not append child to mount");
+                .map_err(|e| DomError::modify_tree("append", e))?;
         }
         self.root_node = created_node.node;
         self.active_closures = created_node.closures;
         log::trace!("focusing element after mounting");
-        self.set_focus_element();
+        self.set_focus_element()
     }
 
-    fn set_focus_element(&self) {
+    fn set_focus_element(&self) -> Result<(), DomError> {
         if let Some(focused_node) = &self.focused_node {
             let focused_element: &Element = focused_node.unchecked_ref();
-            CreatedNode::set_element_focus(focused_element);
+            CreatedNode::set_element_focus(focused_element)
         } else {
             log::warn!("no focused node");
+            Ok(())
         }
     }
 
     /// Mount the current_vdom replacing the actual browser DOM specified in the root_node
     /// This also gets the closures that was created when mounting the vdom to their
     /// actual DOM counterparts.
-    pub fn replace_mount<DSP>(&mut self, program: &DSP)
+    pub fn replace_mount<DSP>(&mut self, program: &DSP) -> Result<(), DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
-        self.mount(program, true);
+        self.mount(program, true)
+    }
+
+    /// Hydrate the root_node, which was already rendered from the current_vdom such as in the
+    /// server, attaching the closures to the existing DOM nodes without recreating them.
+    /// Mismatches between the existing DOM and the current_vdom are repaired.
+    pub fn hydrate<DSP>(&mut self, program: &DSP) -> Result<(), DomError>
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let hydrated_node = CreatedNode::hydrate_dom_node(
+            program,
+            &mut self.node_idx_lookup,
+            &self.current_vdom,
+            &self.root_node,
+            &mut self.focused_node,
+            &mut Some(0),
+        )?;
+        self.root_node = hydrated_node.node;
+        self.active_closures = hydrated_node.closures;
+        log::trace!("focusing element after hydrating");
+        self.set_focus_element()
     }
 
     /// Create a new `DomUpdater`.
@@ -132,13 +165,13 @@ where
         program: &DSP,
         current_vdom: crate::Node<MSG>,
         mount: &Element,
-    ) -> DomUpdater<MSG>
+    ) -> Result<DomUpdater<MSG>, DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
         let mut dom_updater = Self::new(current_vdom, mount);
-        dom_updater.append_to_mount(program);
-        dom_updater
+        dom_updater.append_to_mount(program)?;
+        Ok(dom_updater)
     }
 
     /// Create a new `DomUpdater`.
@@ -149,26 +182,36 @@ where
         program: &DSP,
         current_vdom: crate::Node<MSG>,
         mount: Element,
-    ) -> DomUpdater<MSG>
+    ) -> Result<DomUpdater<MSG>, DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
         let mut dom_updater = Self::new(current_vdom, &mount);
-        dom_updater.replace_mount(program);
-        dom_updater
+        dom_updater.replace_mount(program)?;
+        Ok(dom_updater)
     }
 
     /// Diff the current virtual dom with the new virtual dom that is being passed in.
     ///
     /// Then use that diff to patch the real DOM in the user's browser so that they are
     /// seeing the latest state of the application.
-    pub fn update_dom<DSP>(&mut self, program: &DSP, new_vdom: crate::Node<MSG>)
+    /// The keyed nodes which are moved into another parent are relocated
+    /// together with their event listeners, instead of being recreated.
+    ///
+    /// When a patch fails, the patches before it remain applied and the
+    /// new vdom becomes the current vdom, so the next update is diffed
+    /// from the latest view.
+    pub fn update_dom<DSP>(
+        &mut self,
+        program: &DSP,
+        new_vdom: crate::Node<MSG>,
+    ) -> Result<(), DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
         #[cfg(feature = "with-measure")]
         let t1 = crate::now();
-        let patches = diff(&self.current_vdom, &new_vdom);
+        let patches = diff_with_moves(&self.current_vdom, &new_vdom);
 
         #[cfg(feature = "with-measure")]
         let _t2 = {
@@ -190,17 +233,38 @@ where
             &mut self.node_idx_lookup,
             &mut self.focused_node,
             patches,
-        )
-        .expect("Error in patching the dom");
-
-        self.active_closures.extend(active_closures);
+        );
         self.current_vdom = new_vdom;
-        self.set_focus_element();
+        self.add_active_closures(active_closures)?;
+        self.set_focus_element()
+    }
+
+    /// keep the closures of the applied patches, including the patches
+    /// which are applied before the one that failed, since their event
+    /// listeners are already attached to the DOM
+    fn add_active_closures(
+        &mut self,
+        active_closures: Result<ActiveClosure, (ActiveClosure, DomError)>,
+    ) -> Result<(), DomError> {
+        match active_closures {
+            Ok(active_closures) => {
+                self.active_closures.extend(active_closures);
+                Ok(())
+            }
+            Err((active_closures, error)) => {
+                self.active_closures.extend(active_closures);
+                Err(error)
+            }
+        }
     }
 
     /// Apply patches to the dom updater
     /// Warning: only used this for debuggin purposes
-    pub fn patch_dom<DSP>(&mut self, program: &DSP, patches: Vec<Patch<MSG>>)
+    pub fn patch_dom<DSP>(
+        &mut self,
+        program: &DSP,
+        patches: Vec<Patch<MSG>>,
+    ) -> Result<(), DomError>
     where
         DSP: Dispatch<MSG> + Clone + 'static,
     {
@@ -211,9 +275,8 @@ where
             &mut self.node_idx_lookup,
             &mut self.focused_node,
             patches,
-        )
-        .expect("Error in patching the dom");
-        self.active_closures.extend(active_closures);
+        );
+        self.add_active_closures(active_closures)
     }
 
     /// map this DomUpdater such that the Node<MSG> will become Node<MSG2>
@@ -238,6 +301,20 @@ where
         }
     }
 
+    /// remove the event listeners of all the elements in the DOM, and drop
+    /// their closures
+    pub(crate) fn remove_event_listeners(&mut self) -> Result<(), DomError> {
+        let result = match self.root_node.dyn_ref::<Element>() {
+            Some(root_element) => remove_all_event_listeners(
+                root_element,
+                &mut self.active_closures,
+            ),
+            None => Ok(()),
+        };
+        self.active_closures.clear();
+        result
+    }
+
     /// Return the root node of your application, the highest ancestor of all other nodes in
     /// your real DOM tree.
     pub fn root_node(&self) -> Node {
@@ -0,0 +1,126 @@
+//! the errors in creating and patching the DOM, and in handling the events
+//!
+use crate::dom::util::js_error_message;
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+
+/// The errors which happen while creating and patching the DOM, or while
+/// mapping the browser events. These are reported to the error hook of the
+/// `Program` instead of aborting the app.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum DomError {
+    /// the element can not be created, ie: the tag or the namespace is invalid
+    #[error("Unable to create element `{tag}`: {message}")]
+    CreateElement {
+        /// the tag of the element
+        tag: String,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the attribute can not be set, ie: the name is invalid
+    #[error("Unable to set attribute `{name}`: {message}")]
+    SetAttribute {
+        /// the name of the attribute
+        name: String,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the attribute can not be removed
+    #[error("Unable to remove attribute `{name}`: {message}")]
+    RemoveAttribute {
+        /// the name of the attribute
+        name: String,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the event listener can not be attached or removed
+    #[error("Unable to {action} `{event}` event listener: {message}")]
+    EventListener {
+        /// either `add` or `remove`
+        action: &'static str,
+        /// the name of the event
+        event: String,
+        /// the message of the browser error
+        message: String,
+    },
+    /// a node can not be appended, inserted, replaced or removed
+    #[error("Unable to {action} node: {message}")]
+    ModifyTree {
+        /// ie: `append`, `insert`, `replace`, `remove`
+        action: &'static str,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the node of the patch is not in the DOM, this happens when the DOM is
+    /// modified outside of the app, or the patch is not diffed from the
+    /// current view
+    #[error("Unable to find the node with index {0} to be patched")]
+    NodeNotFound(usize),
+    /// the node has no parent, ie: the root node is detached from the document
+    #[error("The node to be patched is detached from the document")]
+    DetachedNode,
+    /// the element can not be focused
+    #[error("Unable to focus element: {0}")]
+    Focus(String),
+    /// the interval of a subscription can not be set
+    #[error("Unable to set interval: {0}")]
+    SetInterval(String),
+    /// a timeout, an interval or an animation frame of a timer can not be
+    /// set or cancelled
+    #[error("Unable to {action}: {message}")]
+    Timer {
+        /// ie: `set timeout`, `set interval`, `cancel animation frame`
+        action: &'static str,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the url can not be pushed into or replaced in the history of the
+    /// browser, ie: the url is of another origin
+    #[error("Unable to navigate to `{url}`: {message}")]
+    Navigate {
+        /// the url to navigate to
+        url: String,
+        /// the message of the browser error
+        message: String,
+    },
+    /// the event is not of the expected type, or the target of the event is
+    /// not the expected element, ie: an `on_input` in a `div`
+    #[error("Unexpected `{event}` event, expecting {expected}")]
+    UnexpectedEvent {
+        /// the name of the event
+        event: String,
+        /// the expected event type or target
+        expected: &'static str,
+    },
+}
+
+impl DomError {
+    /// creates a `ModifyTree` error from the browser error
+    pub(crate) fn modify_tree(action: &'static str, error: JsValue) -> Self {
+        DomError::ModifyTree {
+            action,
+            message: js_error_message(error),
+        }
+    }
+
+    /// creates a `Timer` error from the browser error
+    pub(crate) fn timer(action: &'static str, error: JsValue) -> Self {
+        DomError::Timer {
+            action,
+            message: js_error_message(error),
+        }
+    }
+
+    /// creates an `EventListener` error from the browser error
+    pub(crate) fn event_listener(
+        action: &'static str,
+        event: &str,
+        error: JsValue,
+    ) -> Self {
+        DomError::EventListener {
+            action,
+            event: event.to_string(),
+            message: js_error_message(error),
+        }
+    }
+}
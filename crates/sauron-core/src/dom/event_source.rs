@@ -0,0 +1,253 @@
+//! provides a subscription to the server-sent events of a url
+//!
+use crate::{
+    sub::{
+        CustomSubscription,
+        Subscription,
+    },
+    Dispatch,
+    Sub,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    any::Any,
+    fmt,
+    rc::Rc,
+};
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+};
+use web_sys::{
+    EventSourceInit,
+    MessageEvent,
+};
+
+/// A stream of server-sent events, the named events are mapped into msgs
+/// with `on` or `on_json`, while the state of the connection is mapped with
+/// `on_state`.
+/// The stream is opened when the subscription is added in the component
+/// `subscriptions`, and is closed when it is no longer in the subscriptions.
+///
+/// Example:
+/// ```rust,ignore
+/// fn subscriptions(&self) -> Sub<Msg> {
+///     EventSource::new("/feed")
+///         .on("message", |message| Msg::Notice(message.data))
+///         .on_json("price", Msg::Price)
+///         .on_state(Msg::FeedState)
+///         .subscribe()
+/// }
+/// ```
+pub struct EventSource<MSG> {
+    url: String,
+    with_credentials: bool,
+    handlers: Vec<(String, ToMsg<MSG>)>,
+    on_state: Option<Rc<dyn Fn(StreamState) -> MSG>>,
+}
+
+/// maps the events with the same name into msgs
+type ToMsg<MSG> = Rc<dyn Fn(ServerMessage) -> MSG>;
+
+/// A named event sent by the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerMessage {
+    /// the name of the event, which is `message` if it is not named
+    pub event: String,
+    /// the data of the event
+    pub data: String,
+    /// the id of the last event, which is sent back by the browser
+    /// when it reconnects
+    pub last_event_id: String,
+}
+
+/// The state of the connection of the stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamState {
+    /// the connection is opened
+    Open,
+    /// the connection is lost, and the browser is reconnecting to the url
+    /// after the retry delay sent by the server
+    Reconnecting,
+    /// the connection failed and is not reconnected, ie: the response status
+    /// is not 200 or the content type is not `text/event-stream`
+    Closed,
+}
+
+/// The opened stream, which is closed when the subscription is dropped
+struct Stream {
+    source: web_sys::EventSource,
+    _closures: Vec<Closure<dyn FnMut(web_sys::Event)>>,
+}
+
+impl<MSG> EventSource<MSG>
+where
+    MSG: 'static,
+{
+    /// create a stream of the server-sent events of the url
+    pub fn new(url: &str) -> Self {
+        EventSource {
+            url: url.to_string(),
+            with_credentials: false,
+            handlers: vec![],
+            on_state: None,
+        }
+    }
+
+    /// send the cookies when the url is in another origin
+    pub fn with_credentials(mut self) -> Self {
+        self.with_credentials = true;
+        self
+    }
+
+    /// map the events with this name into msgs using `f`,
+    /// the events which are not named are `"message"`
+    pub fn on<F>(mut self, event: &str, f: F) -> Self
+    where
+        F: Fn(ServerMessage) -> MSG + 'static,
+    {
+        self.handlers.push((event.to_string(), Rc::new(f)));
+        self
+    }
+
+    /// decode the json data of the events with this name, the result is
+    /// then mapped into a msg using `f`
+    pub fn on_json<T, F>(self, event: &str, f: F) -> Self
+    where
+        T: DeserializeOwned,
+        F: Fn(Result<T, serde_json::Error>) -> MSG + 'static,
+    {
+        self.on(event, move |message| f(message.json()))
+    }
+
+    /// map the changes in the state of the connection into msgs using `f`
+    pub fn on_state<F>(mut self, f: F) -> Self
+    where
+        F: Fn(StreamState) -> MSG + 'static,
+    {
+        self.on_state = Some(Rc::new(f));
+        self
+    }
+
+    /// Creates the subscription which opens the stream, the stream is closed
+    /// when the subscription is removed
+    pub fn subscribe(self) -> Sub<MSG> {
+        let events: Vec<&str> = self
+            .handlers
+            .iter()
+            .map(|(event, _)| event.as_str())
+            .collect();
+        let key = format!("eventsource:{}:{}", self.url, events.join(","));
+        let subscription =
+            CustomSubscription::new(key, move |program| self.open(program));
+        Sub::from(Subscription::Custom(subscription))
+    }
+
+    fn open(&self, program: Rc<dyn Dispatch<MSG>>) -> Box<dyn Any> {
+        let init = EventSourceInit::new();
+        init.set_with_credentials(self.with_credentials);
+        let source = match web_sys::EventSource::new_with_event_source_init_dict(
+            &self.url, &init,
+        ) {
+            Ok(source) => source,
+            Err(e) => {
+                log::error!("Unable to open the event source: {:?}", e);
+                if let Some(on_state) = &self.on_state {
+                    program.dispatch(on_state(StreamState::Closed));
+                }
+                return Box::new(());
+            }
+        };
+
+        let mut closures = vec![];
+        for (event, f) in self.handlers.iter() {
+            let program = Rc::clone(&program);
+            let f = Rc::clone(f);
+            let closure: Closure<dyn FnMut(web_sys::Event)> =
+                Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    let event: MessageEvent = event.unchecked_into();
+                    let message = ServerMessage {
+                        event: event.type_(),
+                        data: event.data().as_string().unwrap_or_default(),
+                        last_event_id: event.last_event_id(),
+                    };
+                    program.dispatch(f(message));
+                }));
+            source
+                .add_event_listener_with_callback(
+                    event,
+                    closure.as_ref().unchecked_ref(),
+                )
+                .expect("Unable to attached event listener");
+            closures.push(closure);
+        }
+
+        if let Some(on_state) = &self.on_state {
+            let program_clone = Rc::clone(&program);
+            let on_open = Rc::clone(on_state);
+            let onopen: Closure<dyn FnMut(web_sys::Event)> =
+                Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                    program_clone.dispatch(on_open(StreamState::Open));
+                }));
+            let source_clone = source.clone();
+            let on_error = Rc::clone(on_state);
+            let onerror: Closure<dyn FnMut(web_sys::Event)> =
+                Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                    // the browser only stops reconnecting when the
+                    // connection failed, ie: the url is not found
+                    let state = if source_clone.ready_state()
+                        == web_sys::EventSource::CLOSED
+                    {
+                        StreamState::Closed
+                    } else {
+                        StreamState::Reconnecting
+                    };
+                    program.dispatch(on_error(state));
+                }));
+            source.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+            source.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+            closures.push(onopen);
+            closures.push(onerror);
+        }
+
+        Box::new(Stream {
+            source,
+            _closures: closures,
+        })
+    }
+}
+
+impl<MSG> fmt::Debug for EventSource<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let events: Vec<&str> = self
+            .handlers
+            .iter()
+            .map(|(event, _)| event.as_str())
+            .collect();
+        f.debug_struct("EventSource")
+            .field("url", &self.url)
+            .field("with_credentials", &self.with_credentials)
+            .field("events", &events)
+            .finish()
+    }
+}
+
+impl ServerMessage {
+    /// decode the json data of this event
+    pub fn json<T>(&self) -> Result<T, serde_json::Error>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_str(&self.data)
+    }
+}
+
+/// The subscription is removed, the stream is closed so the browser
+/// doesn't reconnect to it
+impl Drop for Stream {
+    fn drop(&mut self) {
+        self.source.set_onopen(None);
+        self.source.set_onerror(None);
+        self.source.close();
+    }
+}
@@ -1,6 +1,14 @@
 //! https://developer.mozilla.org/en-US/docs/Web/Events
 
-use crate::{Attribute, Callback};
+use crate::{
+    dom::DomError, html::attributes::AttributeValue, Attribute, Callback,
+};
+use mt_dom::AttValue;
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    rc::Rc,
+};
 use wasm_bindgen::JsCast;
 #[cfg(web_sys_unstable_apis)]
 pub use web_sys::ClipboardEvent;
@@ -16,6 +24,12 @@ pub enum Event {
     WebEvent(web_sys::Event),
     /// custom event here follows
     MountEvent(MountEvent),
+    /// event which is fired without a browser, such as in `TestProgram`
+    SyntheticEvent(SyntheticEvent),
+    /// event which is mapped by the event listener into the value which the
+    /// callback of a typed event listener takes, such as the `MouseEvent`
+    /// of `on_click`
+    MappedEvent(MappedEvent),
 }
 
 impl Event {
@@ -25,6 +39,191 @@ impl Event {
             _ => None,
         }
     }
+
+    /// the name of the event
+    fn name(&self) -> String {
+        match self {
+            Event::WebEvent(web_event) => web_event.type_(),
+            Event::MountEvent(_) => "mount".to_string(),
+            Event::SyntheticEvent(synthetic_event) => {
+                synthetic_event.name.clone()
+            }
+            Event::MappedEvent(mapped_event) => mapped_event.name.clone(),
+        }
+    }
+
+    /// the error when this event is not of the expected type or target
+    fn unexpected(&self, expected: &'static str) -> DomError {
+        DomError::UnexpectedEvent {
+            event: self.name(),
+            expected,
+        }
+    }
+
+    /// cast this event into the specific web_sys event type.
+    /// A synthetic event can not be casted, since there is no actual
+    /// browser event to begin with.
+    fn cast<T>(self, expected: &'static str) -> Result<T, DomError>
+    where
+        T: JsCast,
+    {
+        match self {
+            Event::WebEvent(web_event) if web_event.has_type::<T>() => {
+                Ok(web_event.unchecked_into())
+            }
+            event => Err(event.unexpected(expected)),
+        }
+    }
+}
+
+/// The value of an event which is mapped by the event listener, it is taken
+/// by the callback of the typed event listener which the mapper belongs to.
+#[derive(Clone)]
+pub struct MappedEvent {
+    /// the name of the event which is mapped
+    name: String,
+    value: Rc<dyn Any>,
+}
+
+impl MappedEvent {
+    /// the mapped value if it is of type `T`
+    fn value<T>(&self) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        self.value.downcast_ref::<T>().cloned()
+    }
+}
+
+impl fmt::Debug for MappedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MappedEvent({:?})", self.name)
+    }
+}
+
+impl PartialEq for MappedEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && Rc::as_ptr(&self.value) as *const u8
+                == Rc::as_ptr(&other.value) as *const u8
+    }
+}
+
+/// Maps the events into the value which the callback of a typed event
+/// listener takes, such as the `MouseEvent` of `on_click`.
+///
+/// The mapper is stored in the attribute of the event listener next to its
+/// callback. The event listener maps the event once before calling the
+/// callback, and skips the callback if the event can not be mapped, ie: the
+/// target of an `on_input` is not an input element.
+#[derive(Clone)]
+pub struct EventMapper {
+    /// the type which the events are mapped into
+    type_id: TypeId,
+    type_name: &'static str,
+    map: MapEvent,
+}
+
+/// maps the event into the value of the type which the mapper is created for
+type MapEvent = Rc<dyn Fn(Event) -> Result<Rc<dyn Any>, DomError>>;
+
+impl EventMapper {
+    fn new<T>(mapper: fn(Event) -> Result<T, DomError>) -> Self
+    where
+        T: 'static,
+    {
+        EventMapper {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            map: Rc::new(move |event| {
+                let value: Rc<dyn Any> = Rc::new(mapper(event)?);
+                Ok(value)
+            }),
+        }
+    }
+
+    /// map the event into the value which the callback takes
+    fn map(&self, event: Event) -> Result<Event, DomError> {
+        let name = event.name();
+        let value = (self.map)(event)?;
+        Ok(Event::MappedEvent(MappedEvent { name, value }))
+    }
+}
+
+impl fmt::Debug for EventMapper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EventMapper({})", self.type_name)
+    }
+}
+
+impl PartialEq for EventMapper {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+/// The callback of an event listener attribute, together with the mapper
+/// of the typed event listener which the callback belongs to
+pub(crate) struct Listener<MSG> {
+    callback: Callback<MSG>,
+    mapper: Option<EventMapper>,
+}
+
+impl<MSG> Listener<MSG>
+where
+    MSG: 'static,
+{
+    /// the listeners of the callbacks in this attribute, the mapper of a
+    /// typed event listener is stored right before its callback
+    pub(crate) fn of_attribute(attr: &Attribute<MSG>) -> Vec<Self> {
+        let mut listeners = vec![];
+        let mut mapper = None;
+        for att_value in attr.value() {
+            match att_value {
+                AttValue::Plain(AttributeValue::EventMapper(event_mapper)) => {
+                    mapper = Some(event_mapper.clone());
+                }
+                AttValue::Callback(callback) => {
+                    listeners.push(Listener {
+                        callback: callback.clone(),
+                        mapper: mapper.take(),
+                    });
+                }
+                AttValue::Plain(_) => (),
+            }
+        }
+        listeners
+    }
+
+    /// map the event for the callback, then call the callback with it.
+    /// The events of the listeners which are attached with `on` are passed
+    /// to the callback as is.
+    pub(crate) fn emit(&self, event: Event) -> Result<MSG, DomError> {
+        let event = match &self.mapper {
+            Some(mapper) => mapper.map(event)?,
+            None => event,
+        };
+        Ok(self.callback.emit(event))
+    }
+}
+
+/// An event which is fired programmatically, where there is no browser
+/// to create the actual web_sys::Event, such as in `TestProgram`.
+///
+/// Note: a synthetic event can only be mapped for the listeners which don't
+/// need a browser event, such as `on_input`, `on_focus` or `on`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticEvent {
+    /// the name of the event, ie: "click", "input"
+    pub name: String,
+    /// the value of the target element, which is used in the `InputEvent`
+    pub value: Option<String>,
+}
+
+impl From<SyntheticEvent> for Event {
+    fn from(synthetic_event: SyntheticEvent) -> Self {
+        Event::SyntheticEvent(synthetic_event)
+    }
 }
 
 impl From<MountEvent> for Event {
@@ -47,12 +246,49 @@ where
     mt_dom::on(event_name, Callback::from(f))
 }
 
+/// attach the callback to the event, the mapper is stored next to the
+/// callback, so the event listener maps the event into the type the
+/// callback expects before calling it.
+///
+/// Panics if the callback is emitted directly with an event which is not
+/// mapped and can not be mapped, since there is no msg for it.
+fn on_mapped<T, F, MSG>(
+    event_name: &'static str,
+    mapper: fn(Event) -> Result<T, DomError>,
+    f: F,
+) -> Attribute<MSG>
+where
+    F: Fn(T) -> MSG + 'static,
+    T: Clone + 'static,
+{
+    let listener = on(event_name, move |event: Event| {
+        let mapped = match event {
+            Event::MappedEvent(ref mapped_event) => mapped_event.value(),
+            _ => None,
+        };
+        match mapped {
+            Some(mapped) => f(mapped),
+            None => {
+                match mapper(event) {
+                    Ok(mapped) => f(mapped),
+                    Err(error) => panic!("{}", error),
+                }
+            }
+        }
+    });
+    let event_mapper: Attribute<MSG> = mt_dom::attr(
+        event_name,
+        AttributeValue::EventMapper(EventMapper::new(mapper)),
+    );
+    mt_dom::merge_attributes_of_same_name(&[&event_mapper, &listener]).remove(0)
+}
+
 /// on click event
 pub fn on_click<F, MSG>(f: F) -> Attribute<MSG>
 where
     F: Fn(MouseEvent) -> MSG + 'static,
 {
-    on("click", move |event: Event| f(to_mouse_event(event)))
+    on_mapped("click", to_mouse_event, f)
 }
 
 /// custom on_enter event, which is triggered from key_press when the Enter key is pressed
@@ -60,7 +296,7 @@ pub fn on_enter<F, MSG>(f: F) -> Attribute<MSG>
 where
     F: Fn(KeyboardEvent) -> MSG + 'static,
 {
-    on("enter", move |event: Event| f(to_keyboard_event(event)))
+    on_mapped("enter", to_keyboard_event, f)
 }
 /// attach callback to the scroll event
 pub fn on_scroll<F, MSG>(f: F) -> Attribute<MSG>
@@ -68,15 +304,20 @@ where
     F: Fn((i32, i32)) -> MSG + 'static,
     MSG: 'static,
 {
-    on("scroll", move |event: Event| {
-        let web_event = event.as_web().expect("must be a web event");
-        let target = web_event.target().expect("can't get target");
-        let element: &web_sys::Element =
-            target.dyn_ref().expect("Cant cast to Element");
-        let scroll_top = element.scroll_top();
-        let scroll_left = element.scroll_left();
-        f((scroll_top, scroll_left))
-    })
+    on_mapped("scroll", to_scroll_offset, f)
+}
+
+/// the scroll top and scroll left of the element where the event is fired
+fn to_scroll_offset(event: Event) -> Result<(i32, i32), DomError> {
+    let element = event
+        .clone()
+        .as_web()
+        .and_then(|web_event| web_event.target())
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok());
+    match element {
+        Some(element) => Ok((element.scroll_top(), element.scroll_left())),
+        None => Err(event.unexpected("an element target")),
+    }
 }
 
 /// an event when a virtual Node is mounted the field node is the actual
@@ -92,13 +333,14 @@ pub fn on_mount<F, MSG>(f: F) -> Attribute<MSG>
 where
     F: Fn(MountEvent) -> MSG + 'static,
 {
-    on("mount", move |event: Event| match event {
-        Event::MountEvent(me) => f(me),
-        _ => {
-            log::warn!("was expecting a mount event");
-            unreachable!()
-        }
-    })
+    on_mapped("mount", to_mount_event, f)
+}
+
+fn to_mount_event(event: Event) -> Result<MountEvent, DomError> {
+    match event {
+        Event::MountEvent(mount_event) => Ok(mount_event),
+        event => Err(event.unexpected("a mount event")),
+    }
 }
 
 macro_rules! declare_events {
@@ -117,9 +359,7 @@ macro_rules! declare_events {
                     where CB: Fn($ret) -> MSG + 'static,
                           MSG: 'static,
                     {
-                        on(stringify!($event), move|event:Event|{
-                            cb($mapper(event))
-                        })
+                        on_mapped(stringify!($event), $mapper, cb)
                 }
             }
          )*
@@ -145,67 +385,65 @@ macro_rules! declare_html_events{
 }
 
 /// convert a generic event to MouseEvent
-fn to_mouse_event(event: Event) -> MouseEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event.dyn_into().expect("Unable to cast to mouse event")
+fn to_mouse_event(event: Event) -> Result<MouseEvent, DomError> {
+    event.cast("a MouseEvent")
 }
 
-fn to_keyboard_event(event: Event) -> KeyboardEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to keyboard event")
+fn to_keyboard_event(event: Event) -> Result<KeyboardEvent, DomError> {
+    event.cast("a KeyboardEvent")
 }
 
-fn to_animation_event(event: Event) -> AnimationEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to animation event")
+fn to_animation_event(event: Event) -> Result<AnimationEvent, DomError> {
+    event.cast("an AnimationEvent")
 }
 
-fn to_transition_event(event: Event) -> TransitionEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to transition event")
+fn to_transition_event(event: Event) -> Result<TransitionEvent, DomError> {
+    event.cast("a TransitionEvent")
 }
 
-fn as_is(event: Event) -> Event {
-    event
+fn as_is(event: Event) -> Result<Event, DomError> {
+    Ok(event)
 }
 
-fn to_hashchange_event(event: Event) -> HashChangeEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to hashchange event")
+fn to_hashchange_event(event: Event) -> Result<HashChangeEvent, DomError> {
+    event.cast("a HashChangeEvent")
 }
 
 /// a custom InputEvent to contain the input string value
+#[derive(Debug, Clone, PartialEq)]
 pub struct InputEvent {
     /// the input value
     pub value: String,
-    /// the actual dom event
-    pub event: web_sys::Event,
+    /// the actual dom event, or the synthetic event which is fired
+    /// without a browser
+    pub event: Event,
 }
 
 impl InputEvent {
-    fn new(value: String, event: web_sys::Event) -> Self {
+    fn new(value: String, event: Event) -> Self {
         InputEvent { value, event }
     }
 }
 
-fn to_input_event(event: Event) -> InputEvent {
-    let web_event = event.as_web().expect("must be a web event");
-    let target: EventTarget =
-        web_event.target().expect("Unable to get event target");
-    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
-        InputEvent::new(input.value(), web_event)
-    } else if let Some(textarea) = target.dyn_ref::<HtmlTextAreaElement>() {
-        InputEvent::new(textarea.value(), web_event)
+fn to_input_event(event: Event) -> Result<InputEvent, DomError> {
+    let target: Option<EventTarget> = match &event {
+        Event::SyntheticEvent(synthetic_event) => {
+            let value = synthetic_event.value.clone().unwrap_or_default();
+            return Ok(InputEvent::new(value, event));
+        }
+        Event::WebEvent(web_event) => web_event.target(),
+        Event::MountEvent(_) | Event::MappedEvent(_) => None,
+    };
+    let target = target.as_ref();
+    if let Some(input) = target.and_then(|t| t.dyn_ref::<HtmlInputElement>())
+    {
+        Ok(InputEvent::new(input.value(), event))
+    } else if let Some(textarea) =
+        target.and_then(|t| t.dyn_ref::<HtmlTextAreaElement>())
+    {
+        Ok(InputEvent::new(textarea.value(), event))
     } else {
-        panic!("fail in mapping event into input event");
+        Err(event.unexpected("an input or textarea target"))
     }
 }
 
@@ -213,8 +451,8 @@ fn to_input_event(event: Event) -> InputEvent {
 /// therefore trying to access the data on the target element triggered from paste will get an
 /// empty text
 #[cfg(web_sys_unstable_apis)]
-fn to_clipboard_event(event: Event) -> ClipboardEvent {
-    event.dyn_into().expect("unable to cast to clipboard event")
+fn to_clipboard_event(event: Event) -> Result<ClipboardEvent, DomError> {
+    event.cast("a ClipboardEvent")
 }
 
 // Mouse events
@@ -1,16 +1,64 @@
 //! provides functions for retrieving data using http network request
-use crate::{mt_dom::Callback, Cmd, Component, Dispatch};
-use std::fmt::Debug;
+use crate::{
+    dom::util::js_error_message as error_message,
+    html::attributes::encode_uri_component, mt_dom::Callback, Cmd, Component,
+    Dispatch,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{self, Debug},
+    rc::Rc,
+};
+use thiserror::Error;
 use wasm_bindgen::{closure::Closure, JsCast, JsValue};
-use web_sys::Response;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, Headers, RequestInit, Response};
 
 /// Provides functions for doing http network request
 #[derive(Copy, Clone, Debug)]
 pub struct Http;
 
 impl Http {
+    /// create a GET request to the url
+    pub fn get(url: &str) -> HttpRequest {
+        HttpRequest::new("GET", url)
+    }
+
+    /// create a POST request to the url
+    pub fn post(url: &str) -> HttpRequest {
+        HttpRequest::new("POST", url)
+    }
+
+    /// create a PUT request to the url
+    pub fn put(url: &str) -> HttpRequest {
+        HttpRequest::new("PUT", url)
+    }
+
+    /// create a PATCH request to the url
+    pub fn patch(url: &str) -> HttpRequest {
+        HttpRequest::new("PATCH", url)
+    }
+
+    /// create a DELETE request to the url
+    pub fn delete(url: &str) -> HttpRequest {
+        HttpRequest::new("DELETE", url)
+    }
+
+    /// Creates a Cmd which aborts the in-flight requests of this handle
+    pub fn abort<APP, MSG>(handle: &RequestHandle) -> Cmd<APP, MSG>
+    where
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let handle = handle.clone();
+        Cmd::new(move |_program| handle.abort())
+    }
+
     /// fetch text document from the url and decode the result with the supplied
-    /// response_text_decoder function
+    /// response_text_decoder function.
+    /// The responses with non-2xx status and the failed requests are passed
+    /// to `cb` as the `JsValue` of the message of their `HttpError`.
     pub fn fetch_with_text_response_decoder<DE, CB, OUT, APP, MSG>(
         url: &str,
         response_text_decoder: DE,
@@ -23,20 +71,17 @@ impl Http {
         APP: Component<MSG> + 'static,
         MSG: 'static,
     {
-        let response_text_decoder = Callback::from(response_text_decoder);
-        let cb = Callback::from(cb);
-        let cb_clone = cb.clone();
-        let response_decoder = move |js_value: JsValue| {
-            let response_text =
-                js_value.as_string().expect("There's no string value");
-            let msg_value = response_text_decoder.emit(response_text);
-            cb.emit(Ok(msg_value))
-        };
-        let fail_cb = move |js_value| cb_clone.emit(Err(js_value));
-        Self::fetch_with_response_decoder(url, response_decoder, fail_cb)
-    }
-
-    /// API for fetching http rest request
+        Http::get(url).send(move |result| {
+            cb(success_body(result).map(&response_text_decoder))
+        })
+    }
+
+    /// API for fetching http rest request.
+    /// The text body of a 2xx response is passed to `response_decoder`,
+    /// the responses with other status and the failed requests are passed
+    /// to `fail_cb` as the `JsValue` of the message of their `HttpError`.
+    /// The request can not be aborted, use `HttpRequest::abort_handle` or
+    /// `HttpRequest::latest_wins` for requests which could be superseded.
     pub fn fetch_with_response_decoder<F, ERR, APP, MSG>(
         url: &str,
         response_decoder: F,
@@ -48,66 +93,445 @@ impl Http {
         APP: Component<MSG> + 'static,
         MSG: 'static,
     {
-        let url_clone = url.to_string();
-        let response_decoder = Callback::from(response_decoder);
-
-        let fail_cb = Callback::from(fail_cb);
-
-        let cmd: Cmd<APP, MSG> = Cmd::new(move |program| {
-            let program_clone = program.clone();
-
-            let response_decoder = response_decoder.clone();
-
-            let decoder_and_dispatcher = move |js_value: JsValue| {
-                let msg = response_decoder.emit(js_value);
-                program_clone.dispatch(msg);
-            };
-
-            let fail_cb = fail_cb.clone();
-            let fail_cb2 = fail_cb.clone();
-
-            let program_clone_status_err = program.clone();
-
-            let promise = crate::window().fetch_with_str(&url_clone);
-            let cb: Closure<dyn FnMut(JsValue)> =
-                Closure::once(move |js_value: JsValue| {
-                    let fail_cb = fail_cb.clone();
-                    let response: &Response = js_value.as_ref().unchecked_ref();
-                    let status = response.status();
-                    if status == 200 {
-                        let response_promise = response.text();
-                        if let Ok(response_promise) = response_promise {
-                            let decoder_and_dispatcher_cb: Closure<
-                                dyn FnMut(JsValue),
-                            > = Closure::once(decoder_and_dispatcher);
-
-                            let _ = response_promise
-                                .then(&decoder_and_dispatcher_cb);
-
-                            decoder_and_dispatcher_cb.forget();
-                        } else {
-                            panic!("Expecting a string");
-                        }
-                    } else {
-                        program_clone_status_err
-                            .dispatch(fail_cb.emit(js_value));
-                    }
-                });
-
-            let program_clone_response_error = program;
-            let fail_closure: Closure<dyn FnMut(JsValue)> =
-                Closure::once(move |js_value: JsValue| {
-                    let fail_cb = fail_cb2.clone();
-                    program_clone_response_error
-                        .dispatch(fail_cb.emit(js_value));
-                });
-
-            let _ = promise.then(&cb).catch(&fail_closure);
-
-            cb.forget();
-            fail_closure.forget();
-        });
-
-        cmd
+        Http::get(url).send(move |result| match success_body(result) {
+            Ok(body) => response_decoder(JsValue::from_str(&body)),
+            Err(error) => fail_cb(error),
+        })
+    }
+}
+
+/// the body of a 2xx response, the responses with other status and the
+/// errors are converted into the `JsValue` of their message, which is what
+/// the legacy fetch functions pass to their callbacks
+fn success_body(
+    result: Result<HttpResponse, HttpError>,
+) -> Result<String, JsValue> {
+    match result {
+        Ok(response) if response.is_success() => Ok(response.body),
+        Ok(response) => {
+            Err(JsValue::from_str(&HttpError::Status(response).to_string()))
+        }
+        Err(error) => Err(JsValue::from_str(&error.to_string())),
+    }
+}
+
+/// An http request which is sent when the Cmd returned from `send` or
+/// `send_json` is emitted.
+///
+/// Example:
+/// ```rust,ignore
+/// Http::post("/api/articles")
+///     .header("Authorization", &token)
+///     .json_body(&article)
+///     .send_json(Msg::ArticleSaved)
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    /// an error which is encountered while building the request,
+    /// this is returned when the request is sent
+    error: Option<HttpError>,
+    /// abort the request if there is no response after this milliseconds
+    timeout: Option<i32>,
+    /// the handle which can abort this request
+    handle: Option<RequestHandle>,
+    /// abort the previous requests of the handle when this is sent
+    latest_wins: bool,
+}
+
+/// A handle to the requests which are sent with it, used for aborting them.
+/// Dropping the handle doesn't abort the requests.
+///
+/// Example:
+/// ```rust,ignore
+/// // in the update, each keystroke aborts the previous search
+/// Http::get(&format!("/search?q={}", query))
+///     .latest_wins(&self.search)
+///     .send_json(Msg::SearchResult)
+/// ```
+#[derive(Clone, Default)]
+pub struct RequestHandle {
+    in_flight: Rc<RefCell<Vec<AbortController>>>,
+}
+
+/// The response of the http request
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    /// the status code of the response
+    pub status: u16,
+    /// the status message of the response
+    pub status_text: String,
+    /// the headers of the response, names are in lowercase
+    pub headers: Vec<(String, String)>,
+    /// the body of the response
+    pub body: String,
+}
+
+/// The possible errors of an http request
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum HttpError {
+    /// the request can not be created, ie: invalid url, method or header,
+    /// or the body can not be serialized
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    /// the request failed without a response, ie: network failure
+    /// or blocked by CORS
+    #[error("Network error: {0}")]
+    Network(String),
+    /// the server responded with a status other than 2xx
+    #[error("Request failed with status: {} {}", .0.status, .0.status_text)]
+    Status(HttpResponse),
+    /// the response body can not be decoded
+    #[error("Unable to decode response: {0}")]
+    Decode(String),
+    /// the request is aborted by its `RequestHandle`,
+    /// or is superseded by a newer request in `latest_wins` mode
+    #[error("Request is aborted")]
+    Aborted,
+    /// there is no response within the timeout of the request
+    #[error("Request timed out")]
+    TimedOut,
+}
+
+impl HttpRequest {
+    /// create a request with this method and url
+    pub fn new(method: &str, url: &str) -> Self {
+        HttpRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: vec![],
+            body: None,
+            error: None,
+            timeout: None,
+            handle: None,
+            latest_wins: false,
+        }
+    }
+
+    /// abort the request with `HttpError::TimedOut` if the response has not
+    /// arrived after the timeout in milliseconds
+    pub fn timeout(mut self, timeout: i32) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// the request can be aborted using the handle, the request then
+    /// returns `HttpError::Aborted`
+    pub fn abort_handle(mut self, handle: &RequestHandle) -> Self {
+        self.handle = Some(handle.clone());
+        self.latest_wins = false;
+        self
+    }
+
+    /// the in-flight requests of the handle are aborted when this request is
+    /// sent, so the response of a superseded request never overwrites the
+    /// response of this request.
+    pub fn latest_wins(mut self, handle: &RequestHandle) -> Self {
+        self.handle = Some(handle.clone());
+        self.latest_wins = true;
+        self
     }
+
+    /// add a header to the request
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// set the body of the request with a text
+    pub fn text_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    /// set the body of the request with the json serialization of `value`,
+    /// the content-type is set to `application/json`
+    pub fn json_body<T: Serialize>(mut self, value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(json) => self.body = Some(json),
+            Err(e) => {
+                self.error = Some(HttpError::InvalidRequest(e.to_string()))
+            }
+        }
+        self.default_content_type("application/json")
+    }
+
+    /// set the body of the request with url encoded form fields,
+    /// the content-type is set to `application/x-www-form-urlencoded`
+    pub fn form_body(mut self, fields: &[(&str, &str)]) -> Self {
+        let form = fields
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "{}={}",
+                    encode_uri_component(name),
+                    encode_uri_component(value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        self.body = Some(form);
+        self.default_content_type("application/x-www-form-urlencoded")
+    }
+
+    /// set the content-type header, if it is not set yet
+    fn default_content_type(self, content_type: &str) -> Self {
+        let has_content_type = self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        if has_content_type {
+            self
+        } else {
+            self.header("Content-Type", content_type)
+        }
+    }
+
+    /// Creates a Cmd which sends this request, the result is then mapped into
+    /// a msg using `f`.
+    /// Responses with non-2xx status are also returned as `Ok`, the status
+    /// can be inspected in the `HttpResponse`
+    pub fn send<F, APP, MSG>(self, f: F) -> Cmd<APP, MSG>
+    where
+        F: Fn(Result<HttpResponse, HttpError>) -> MSG + 'static,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let cb = Callback::from(f);
+        Cmd::new(move |program| {
+            let request = self.clone();
+            let cb = cb.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = request.fetch().await;
+                program.dispatch(cb.emit(result));
+            });
+        })
+    }
+
+    /// Creates a Cmd which sends this request and decodes the json response
+    /// body into `T`, the result is then mapped into a msg using `f`.
+    /// Responses with non-2xx status are returned as `HttpError::Status`.
+    pub fn send_json<T, F, APP, MSG>(self, f: F) -> Cmd<APP, MSG>
+    where
+        T: DeserializeOwned + 'static,
+        F: Fn(Result<T, HttpError>) -> MSG + 'static,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        self.default_accept("application/json")
+            .send(move |result| f(result.and_then(|r| r.json())))
+    }
+
+    /// set the accept header, if it is not set yet
+    fn default_accept(self, accept: &str) -> Self {
+        let has_accept = self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("accept"));
+        if has_accept {
+            self
+        } else {
+            self.header("Accept", accept)
+        }
+    }
+
+    /// send the request and wait for the response
+    pub async fn fetch(self) -> Result<HttpResponse, HttpError> {
+        let controller = AbortController::new()
+            .map_err(|e| HttpError::InvalidRequest(error_message(e)))?;
+        let request = self.to_web_request(&controller)?;
+        let timeout = self
+            .timeout
+            .map(|ms| Timeout::start(&controller, ms))
+            .transpose()?;
+        if let Some(handle) = &self.handle {
+            if self.latest_wins {
+                handle.abort();
+            }
+            handle.in_flight.borrow_mut().push(controller.clone());
+        }
+
+        let result = self.fetch_response(request).await;
+
+        if let Some(handle) = &self.handle {
+            handle.in_flight.borrow_mut().retain(|c| *c != controller);
+        }
+        let timed_out = timeout.map(|t| t.has_fired()).unwrap_or(false);
+        if timed_out {
+            Err(HttpError::TimedOut)
+        } else if controller.signal().aborted() {
+            // the response may have arrived just before the request is
+            // aborted, it is dropped since the request is no longer wanted
+            Err(HttpError::Aborted)
+        } else {
+            result
+        }
+    }
+
+    async fn fetch_response(
+        &self,
+        request: web_sys::Request,
+    ) -> Result<HttpResponse, HttpError> {
+        let fetch = crate::window().fetch_with_request(&request);
+        let response = JsFuture::from(fetch)
+            .await
+            .map_err(|e| HttpError::Network(error_message(e)))?;
+        let response: Response = response.unchecked_into();
+        let body = response
+            .text()
+            .map_err(|e| HttpError::Decode(error_message(e)))?;
+        let body = JsFuture::from(body)
+            .await
+            .map_err(|e| HttpError::Decode(error_message(e)))?
+            .as_string()
+            .unwrap_or_default();
+        Ok(HttpResponse {
+            status: response.status(),
+            status_text: response.status_text(),
+            headers: header_entries(&response.headers()),
+            body,
+        })
+    }
+
+    fn to_web_request(
+        &self,
+        controller: &AbortController,
+    ) -> Result<web_sys::Request, HttpError> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        let invalid = |e| HttpError::InvalidRequest(error_message(e));
+        let headers = Headers::new().map_err(invalid)?;
+        for (name, value) in self.headers.iter() {
+            headers.append(name, value).map_err(invalid)?;
+        }
+        let init = RequestInit::new();
+        init.set_method(&self.method);
+        init.set_headers(&headers);
+        init.set_signal(Some(&controller.signal()));
+        if let Some(body) = &self.body {
+            init.set_body(&JsValue::from_str(body));
+        }
+        web_sys::Request::new_with_str_and_init(&self.url, &init)
+            .map_err(invalid)
+    }
+}
+
+impl RequestHandle {
+    /// create a handle with no requests
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// abort all the in-flight requests of this handle,
+    /// each of which then returns `HttpError::Aborted`
+    pub fn abort(&self) {
+        let in_flight = std::mem::take(&mut *self.in_flight.borrow_mut());
+        for controller in in_flight {
+            controller.abort();
+        }
+    }
+
+    /// returns true if there are requests of this handle which are
+    /// still waiting for the response
+    pub fn is_pending(&self) -> bool {
+        !self.in_flight.borrow().is_empty()
+    }
+}
+
+impl fmt::Debug for RequestHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RequestHandle {{ is_pending: {} }}", self.is_pending())
+    }
+}
+
+/// Aborts the request when the timeout is reached,
+/// the timeout is cleared when this is dropped
+struct Timeout {
+    id: i32,
+    fired: Rc<Cell<bool>>,
+    _closure: Closure<dyn FnMut()>,
+}
+
+impl Timeout {
+    fn start(
+        controller: &AbortController,
+        timeout: i32,
+    ) -> Result<Self, HttpError> {
+        let fired = Rc::new(Cell::new(false));
+        let fired_clone = Rc::clone(&fired);
+        let controller = controller.clone();
+        let closure: Closure<dyn FnMut()> = Closure::wrap(Box::new(move || {
+            fired_clone.set(true);
+            controller.abort();
+        }));
+        let id = crate::window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                timeout,
+            )
+            .map_err(|e| {
+                HttpError::InvalidRequest(format!(
+                    "Unable to set timeout: {}",
+                    error_message(e)
+                ))
+            })?;
+        Ok(Timeout {
+            id,
+            fired,
+            _closure: closure,
+        })
+    }
+
+    fn has_fired(&self) -> bool {
+        self.fired.get()
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        crate::window().clear_timeout_with_handle(self.id);
+    }
+}
+
+impl HttpResponse {
+    /// returns true if the status is 2xx
+    pub fn is_success(&self) -> bool {
+        self.status >= 200 && self.status < 300
+    }
+
+    /// get the value of the header with this name, case insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// decode the json body into `T`,
+    /// returns `HttpError::Status` if the status is not 2xx
+    pub fn json<T: DeserializeOwned>(self) -> Result<T, HttpError> {
+        if !self.is_success() {
+            return Err(HttpError::Status(self));
+        }
+        serde_json::from_str(&self.body)
+            .map_err(|e| HttpError::Decode(e.to_string()))
+    }
+}
+
+/// get the name and value of each header
+fn header_entries(headers: &Headers) -> Vec<(String, String)> {
+    let entries = match js_sys::try_iter(headers) {
+        Ok(Some(entries)) => entries,
+        _ => return vec![],
+    };
+    entries
+        .filter_map(|entry| {
+            let entry: js_sys::Array = entry.ok()?.unchecked_into();
+            Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+        })
+        .collect()
 }
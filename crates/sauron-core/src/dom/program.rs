@@ -1,17 +1,34 @@
 use crate::{
-    dom::dom_updater::DomUpdater,
+    dom::{
+        dom_updater::DomUpdater,
+        subscriptions::ActiveSubscriptions,
+        DomError,
+    },
+    html::LazyScope,
+    render::DATA_SAURON_STYLE,
     Cmd,
     Component,
     Dispatch,
+    MsgQueue,
 };
+use serde::de::DeserializeOwned;
 use std::{
-    cell::RefCell,
+    cell::{
+        Cell,
+        RefCell,
+    },
     rc::Rc,
 };
 #[cfg(feature = "with-request-animation-frame")]
 use wasm_bindgen::closure::Closure;
 use web_sys::Node;
 
+/// receives the errors in patching the DOM and in handling the events
+type ErrorHook = Rc<dyn Fn(DomError)>;
+
+/// receives the msgs before they are updated into the app
+type MsgHook<MSG> = Rc<dyn Fn(&MSG)>;
+
 /// Holds the user App and the dom updater
 /// This is passed into the event listener and the dispatch program
 /// will be called after the event is triggered.
@@ -25,6 +42,28 @@ where
     pub app: Rc<RefCell<APP>>,
     /// The dom_updater responsible to updating the actual document in the browser
     pub dom_updater: Rc<RefCell<DomUpdater<MSG>>>,
+    /// the dispatched msgs which are not yet processed
+    pending_msgs: MsgQueue<MSG>,
+    /// true if there is already a frame requested to process the pending msgs
+    #[cfg(feature = "with-request-animation-frame")]
+    frame_requested: Rc<Cell<bool>>,
+    /// true while the pending msgs are processed, the msgs which are
+    /// dispatched meanwhile are processed in the same pass
+    #[cfg(not(feature = "with-request-animation-frame"))]
+    dispatching: Rc<Cell<bool>>,
+    /// the number of msgs which are updated in the last render
+    last_merged_updates: Rc<Cell<usize>>,
+    /// the listeners of the app subscriptions
+    subscriptions: Rc<RefCell<ActiveSubscriptions<MSG>>>,
+    /// the errors are reported here, instead of aborting the app
+    error_hook: Rc<RefCell<Option<ErrorHook>>>,
+    /// the msgs are passed here before they are updated into the app
+    msg_hook: Rc<RefCell<Option<MsgHook<MSG>>>>,
+    /// the scope of the lazy nodes in the view
+    lazy_scope: Rc<LazyScope>,
+    /// true if the program is stopped, the msgs dispatched after it are
+    /// dropped
+    stopped: Rc<Cell<bool>>,
 }
 
 impl<APP, MSG> Clone for Program<APP, MSG>
@@ -35,6 +74,47 @@ where
         Program {
             app: Rc::clone(&self.app),
             dom_updater: Rc::clone(&self.dom_updater),
+            pending_msgs: self.pending_msgs.clone(),
+            #[cfg(feature = "with-request-animation-frame")]
+            frame_requested: Rc::clone(&self.frame_requested),
+            #[cfg(not(feature = "with-request-animation-frame"))]
+            dispatching: Rc::clone(&self.dispatching),
+            last_merged_updates: Rc::clone(&self.last_merged_updates),
+            subscriptions: Rc::clone(&self.subscriptions),
+            error_hook: Rc::clone(&self.error_hook),
+            msg_hook: Rc::clone(&self.msg_hook),
+            lazy_scope: Rc::clone(&self.lazy_scope),
+            stopped: Rc::clone(&self.stopped),
+        }
+    }
+}
+
+impl<APP, MSG> Program<APP, MSG>
+where
+    MSG: 'static,
+{
+    /// Stop the program, such as when the `SubComponent` which it runs is
+    /// dropped. The listeners of the subscriptions and of the view are
+    /// removed, the msg hook and the cached lazy views are dropped, and the
+    /// msgs which are dispatched afterwards, such as from a timer, are ignored.
+    pub(crate) fn stop(&self) {
+        self.stopped.set(true);
+        self.pending_msgs.take();
+        *self.msg_hook.borrow_mut() = None;
+        *self.subscriptions.borrow_mut() = ActiveSubscriptions::new();
+        let result = self.dom_updater.borrow_mut().remove_event_listeners();
+        if let Err(error) = result {
+            self.pass_error(error);
+        }
+        self.lazy_scope.clear();
+    }
+
+    /// pass the error to the error hook, or log it if there is no hook
+    fn pass_error(&self, error: DomError) {
+        let error_hook = self.error_hook.borrow().clone();
+        match error_hook {
+            Some(error_hook) => error_hook(error),
+            None => log::error!("{}", error),
         }
     }
 }
@@ -47,11 +127,23 @@ where
     /// Create an Rc wrapped instance of program, initializing DomUpdater with the initial view
     /// and root node, but doesn't mount it yet.
     fn new(app: APP, root_node: &Node) -> Self {
+        let lazy_scope = LazyScope::new();
         let dom_updater: DomUpdater<MSG> =
-            DomUpdater::new(app.view(), root_node);
+            DomUpdater::new(lazy_scope.view(|| app.view()), root_node);
         let program = Program {
             app: Rc::new(RefCell::new(app)),
             dom_updater: Rc::new(RefCell::new(dom_updater)),
+            pending_msgs: MsgQueue::new(),
+            #[cfg(feature = "with-request-animation-frame")]
+            frame_requested: Rc::new(Cell::new(false)),
+            #[cfg(not(feature = "with-request-animation-frame"))]
+            dispatching: Rc::new(Cell::new(false)),
+            last_merged_updates: Rc::new(Cell::new(0)),
+            subscriptions: Rc::new(RefCell::new(ActiveSubscriptions::new())),
+            error_hook: Rc::new(RefCell::new(None)),
+            msg_hook: Rc::new(RefCell::new(None)),
+            lazy_scope: Rc::new(lazy_scope),
+            stopped: Rc::new(Cell::new(false)),
         };
         program.init_emit();
         program
@@ -62,7 +154,7 @@ where
         let cmds: Cmd<APP, MSG> = self.app.borrow().init();
         // then emit the cmds, so it starts executing initial calls such (ie: fetching data,
         // listening to events (resize, hashchange)
-        cmds.emit(&self);
+        cmds.emit(self);
     }
 
     /// executed after the program has been mounted
@@ -70,11 +162,53 @@ where
         for style in self.app.borrow().style() {
             Self::inject_style(&style);
         }
+        // the msgs which are dispatched as the subscriptions are started
+        // are processed after the subscriptions are updated
+        #[cfg(not(feature = "with-request-animation-frame"))]
+        self.dispatching.set(true);
+        self.update_subscriptions();
+        #[cfg(not(feature = "with-request-animation-frame"))]
+        self.dispatch_queued();
+    }
+
+    /// evaluate the subscriptions of the app, attaching listeners for the new
+    /// subscriptions and removing the ones which are no longer subscribed
+    fn update_subscriptions(&self) {
+        let sub = self.app.borrow().subscriptions();
+        self.subscriptions.borrow_mut().update(self, sub);
+    }
+
+    /// Set the hook which receives the errors in patching the DOM and in
+    /// mapping the events, such as a bad patch, a detached root node or an
+    /// event with an unexpected target. The app keeps on running after the
+    /// error, the errors are only logged when there is no hook.
+    ///
+    /// Note: the errors in mounting the app happen before the hook is set,
+    /// and are logged.
+    pub fn on_error<F>(&self, f: F)
+    where
+        F: Fn(DomError) + 'static,
+    {
+        *self.error_hook.borrow_mut() = Some(Rc::new(f));
+    }
+
+    /// Set the hook which receives a reference of every msg before it is
+    /// updated into the app, such as to pass the msgs of a `SubComponent`
+    /// to its parent.
+    pub fn on_msg<F>(&self, f: F)
+    where
+        F: Fn(&MSG) + 'static,
+    {
+        *self.msg_hook.borrow_mut() = Some(Rc::new(f));
+    }
+
+    /// the number of subscriptions which have listeners attached
+    pub fn active_subscription_len(&self) -> usize {
+        self.subscriptions.borrow().len()
     }
 
     /// get the real DOM node where this app is mounted to.
-    #[allow(unused)]
-    fn root_node(&self) -> web_sys::Node {
+    pub(crate) fn root_node(&self) -> web_sys::Node {
         self.dom_updater.borrow().root_node()
     }
 
@@ -94,40 +228,134 @@ where
         program
     }
 
+    /// Instantiate the app and hydrate the root_node, which was rendered from the same app view
+    /// in the server using `Render`.
+    /// The existing DOM nodes are reused and only the event listeners are attached to them,
+    /// so the server rendered markup will not be thrown away.
+    pub fn hydrate(app: APP, root_node: &Node) -> Self {
+        let program = Self::new(app, root_node);
+        program.start_hydrate();
+        program.after_mounted();
+        program
+    }
+
+    /// Instantiate the app from the serialized state which was rendered in the server
+    /// using `render_with_state`, then hydrate the root_node which was rendered from that state.
+    ///
+    /// Returns `None` if the page has no serialized state, or the state can not be
+    /// deserialized, such as in a stale page from the cache. The app can then be mounted
+    /// with `Program::hydrate` instead, which repairs the mismatches in the markup.
+    pub fn resume(root_node: &Node) -> Option<Self>
+    where
+        APP: DeserializeOwned,
+    {
+        let app = Self::read_serialized_state()?;
+        Some(Self::hydrate(app, root_node))
+    }
+
+    /// read and deserialize the state of the app which was rendered in the server
+    /// using `render_with_state`
+    pub fn read_serialized_state() -> Option<APP>
+    where
+        APP: DeserializeOwned,
+    {
+        let script = crate::document()
+            .get_element_by_id(crate::SERIALIZED_STATE_ID);
+        if script.is_none() {
+            log::warn!(
+                "there is no element with id: {}",
+                crate::SERIALIZED_STATE_ID
+            );
+        }
+        let json = script?.text_content()?;
+        match serde_json::from_str(&json) {
+            Ok(app) => Some(app),
+            Err(e) => {
+                log::error!("unable to deserialize the app state: {}", e);
+                None
+            }
+        }
+    }
+
     /// Instantiate the app and then append it to the document body
     pub fn mount_to_body(app: APP) -> Self {
         Self::new_append_to_mount(app, &crate::body())
     }
 
     fn start_append_to_mount(&self) {
-        self.dom_updater.borrow_mut().append_to_mount(self)
+        let result = self.dom_updater.borrow_mut().append_to_mount(self);
+        if let Err(error) = result {
+            self.report_error(error);
+        }
     }
 
     fn start_replace_mount(&self) {
-        self.dom_updater.borrow_mut().replace_mount(self)
+        let result = self.dom_updater.borrow_mut().replace_mount(self);
+        if let Err(error) = result {
+            self.report_error(error);
+        }
+    }
+
+    fn start_hydrate(&self) {
+        let result = self.dom_updater.borrow_mut().hydrate(self);
+        if let Err(error) = result {
+            self.report_error(error);
+        }
+    }
+
+    /// The number of msgs that are merged in the last render.
+    /// The msgs which are dispatched before the next animation frame are all
+    /// updated into the app first, before the view is created and
+    /// the DOM is updated just once.
+    pub fn last_merged_updates(&self) -> usize {
+        self.last_merged_updates.get()
     }
 
     /// This is called when an event is triggered in the html DOM.
     /// The sequence of things happening here:
-    /// - The app component update is executed.
+    /// - The app component update is executed for each of the pending msgs.
     /// - The returned Cmd from the component update is then emitted.
+    /// - The subscriptions of the app are updated.
+    /// - If none of the Cmds need a re-render, the next steps are skipped.
     /// - The view is reconstructed with the new state of the app.
     /// - The dom is updated with the newly reconstructed view.
-    fn dispatch_inner(&self, msg: MSG) {
+    fn dispatch_pending(&self) {
         #[cfg(feature = "with-measure")]
         let t1 = crate::now();
-        // update the app and emit the cmd returned from the update
-        let cmd = self.app.borrow_mut().update(msg);
-        cmd.emit(self);
-        //trace!("Executing cmd..");
+        // update the app and emit the cmd returned from the update,
+        // msgs that are dispatched from the cmd are also updated in this pass
+        let mut updates = 0;
+        let mut should_update = false;
+        let msg_hook = self.msg_hook.borrow().clone();
+        while let Some(msg) = self.pending_msgs.pop() {
+            if let Some(msg_hook) = &msg_hook {
+                msg_hook(&msg);
+            }
+            let cmd = self.app.borrow_mut().update(msg);
+            should_update |= cmd.should_update;
+            cmd.emit(self);
+            updates += 1;
+        }
+        // msgs dispatched while updating the dom are in the next frame
+        #[cfg(feature = "with-request-animation-frame")]
+        self.frame_requested.set(false);
+        if updates > 0 {
+            self.update_subscriptions();
+        }
+        // skip the view and the dom update when all of the updates
+        // returned a Cmd which don't need to re-render
+        if !should_update {
+            return;
+        }
+        self.last_merged_updates.set(updates);
         #[cfg(feature = "with-measure")]
         let t2 = {
             let t2 = crate::now();
-            log::trace!("app update took: {}ms", t2 - t1);
+            log::trace!("app update of {} msgs took: {}ms", updates, t2 - t1);
             t2
         };
         // a new view is created due to the app update
-        let view = self.app.borrow().view();
+        let view = self.lazy_scope.view(|| self.app.borrow().view());
         #[cfg(feature = "with-measure")]
         let t3 = {
             let t3 = crate::now();
@@ -135,7 +363,10 @@ where
             t3
         };
         // update the last DOM node tree with this new view
-        self.dom_updater.borrow_mut().update_dom(self, view);
+        let result = self.dom_updater.borrow_mut().update_dom(self, view);
+        if let Err(error) = result {
+            self.report_error(error);
+        }
         #[cfg(feature = "with-measure")]
         {
             let t4 = crate::now();
@@ -150,10 +381,27 @@ where
         };
     }
 
+    /// process the pending msgs until there is none left.
+    /// The msgs which are dispatched meanwhile, such as from a subscription
+    /// which emits as it is started, are only queued, so the subscriptions
+    /// and the dom are not updated while they are being updated.
+    #[cfg(not(feature = "with-request-animation-frame"))]
+    fn dispatch_queued(&self) {
+        self.dispatching.set(true);
+        while !self.pending_msgs.is_empty() {
+            self.dispatch_pending();
+        }
+        self.dispatching.set(false);
+    }
+
     fn inject_style(style: &str) {
         use wasm_bindgen::JsCast;
 
         let document = crate::document();
+        let head = document.head().expect("must have a head");
+        if Self::is_style_inlined(&head, style) {
+            return;
+        }
         let html_style = document
             .create_element("style")
             .expect("must be able to create style element");
@@ -162,9 +410,22 @@ where
             .expect("must set attribute");
         let html_style: web_sys::Node = html_style.unchecked_into();
         html_style.set_text_content(Some(style));
-        let head = document.head().expect("must have a head");
         head.append_child(&html_style).expect("must append style");
     }
+
+    /// true if the style is already inlined in the head by `HtmlDocument`,
+    /// such as in the server rendered page which is hydrated
+    fn is_style_inlined(head: &web_sys::HtmlHeadElement, style: &str) -> bool {
+        let selector = format!("style[{}]", DATA_SAURON_STYLE);
+        match head.query_selector_all(&selector) {
+            Ok(inlined) => {
+                (0..inlined.length())
+                    .filter_map(|i| inlined.item(i))
+                    .any(|node| node.text_content().as_deref() == Some(style))
+            }
+            Err(_) => false,
+        }
+    }
 }
 
 /// This will be called when the actual event is triggered.
@@ -174,12 +435,22 @@ where
     MSG: 'static,
     APP: Component<MSG> + 'static,
 {
+    /// The msg is queued and all the pending msgs are processed
+    /// in the next animation frame.
     #[cfg(feature = "with-request-animation-frame")]
     fn dispatch(&self, msg: MSG) {
+        if self.stopped.get() {
+            return;
+        }
+        self.pending_msgs.dispatch(msg);
+        if self.frame_requested.get() {
+            return;
+        }
+        self.frame_requested.set(true);
         let program_clone = self.clone();
         let closure_raf: Closure<dyn FnMut() + 'static> =
             Closure::once(move || {
-                program_clone.dispatch_inner(msg);
+                program_clone.dispatch_pending();
             });
         crate::request_animation_frame(&closure_raf);
         closure_raf.forget();
@@ -187,6 +458,17 @@ where
 
     #[cfg(not(feature = "with-request-animation-frame"))]
     fn dispatch(&self, msg: MSG) {
-        self.dispatch_inner(msg)
+        if self.stopped.get() {
+            return;
+        }
+        self.pending_msgs.dispatch(msg);
+        if !self.dispatching.get() {
+            self.dispatch_queued();
+        }
+    }
+
+    /// The error is passed to the error hook, or is logged if there is no hook
+    fn report_error(&self, error: DomError) {
+        self.pass_error(error)
     }
 }
@@ -0,0 +1,278 @@
+//! provides Cmds and subscriptions for persisting the app data in the
+//! localStorage or sessionStorage of the browser
+//!
+use crate::{
+    dom::{
+        subscriptions::EventListener,
+        util::js_error_message as error_message,
+    },
+    sub::{
+        CustomSubscription,
+        Subscription,
+    },
+    Cmd,
+    Component,
+    Dispatch,
+    Sub,
+};
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::{
+    any::Any,
+    rc::Rc,
+};
+use thiserror::Error;
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+};
+use web_sys::StorageEvent;
+
+/// Stores serde serializable values in the localStorage or sessionStorage,
+/// the keys are prefixed with the namespace such that apps which are
+/// served in the same origin don't overwrite each other's data.
+///
+/// Example:
+/// ```rust,ignore
+/// let storage = Storage::local("todomvc");
+/// // in the update
+/// storage.save("data", &self.entries, Msg::StorageError)
+/// // in the subscriptions, to sync the changes from the other tabs
+/// storage.on_change("data", Msg::EntriesChanged)
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Storage {
+    area: StorageArea,
+    namespace: String,
+}
+
+/// Where the values are stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageArea {
+    /// `window.localStorage`, the values are kept after the browser is closed
+    Local,
+    /// `window.sessionStorage`, the values are cleared when the page session
+    /// ends
+    Session,
+}
+
+/// The possible errors when accessing the storage
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum StorageError {
+    /// the storage is disabled or is not accessible, ie: in private mode
+    #[error("Storage is not available: {0}")]
+    Unavailable(String),
+    /// the value can not be written, ie: the quota is exceeded
+    #[error("Unable to write to storage: {0}")]
+    Write(String),
+    /// the value can not be serialized into json
+    #[error("Unable to serialize: {0}")]
+    Serialize(String),
+    /// the stored value can not be deserialized into the expected type
+    #[error("Unable to deserialize: {0}")]
+    Deserialize(String),
+}
+
+impl Storage {
+    /// store the values in the localStorage with this namespace
+    pub fn local(namespace: &str) -> Self {
+        Storage {
+            area: StorageArea::Local,
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// store the values in the sessionStorage with this namespace
+    pub fn session(namespace: &str) -> Self {
+        Storage {
+            area: StorageArea::Session,
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// the key in the storage, which is prefixed with the namespace,
+    /// ie: `todomvc::data`
+    pub fn namespaced_key(&self, key: &str) -> String {
+        if self.namespace.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}::{}", self.namespace, key)
+        }
+    }
+
+    /// get the value of the key, returns `Ok(None)` if there is no value
+    /// stored with the key.
+    /// This can be used to load the app state before it is mounted.
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>, StorageError>
+    where
+        T: DeserializeOwned,
+    {
+        let value = self
+            .web_storage()?
+            .get_item(&self.namespaced_key(key))
+            .map_err(|e| StorageError::Unavailable(error_message(e)))?;
+        value.map(|value| deserialize(&value)).transpose()
+    }
+
+    /// store the value with the key
+    pub fn set<T>(&self, key: &str, value: &T) -> Result<(), StorageError>
+    where
+        T: Serialize,
+    {
+        let json = serde_json::to_string(value)
+            .map_err(|e| StorageError::Serialize(e.to_string()))?;
+        self.set_item(key, &json)
+    }
+
+    /// remove the value of the key
+    pub fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.web_storage()?
+            .remove_item(&self.namespaced_key(key))
+            .map_err(|e| StorageError::Write(error_message(e)))
+    }
+
+    /// Creates a Cmd which gets the value of the key, the result is then
+    /// mapped into a msg using `f`
+    pub fn load<T, F, APP, MSG>(&self, key: &str, f: F) -> Cmd<APP, MSG>
+    where
+        T: DeserializeOwned,
+        F: Fn(Result<Option<T>, StorageError>) -> MSG + 'static,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let storage = self.clone();
+        let key = key.to_string();
+        Cmd::new(move |program| program.dispatch(f(storage.get(&key))))
+    }
+
+    /// Creates a Cmd which stores the value with the key.
+    /// The value is serialized right away, while the errors are mapped into
+    /// a msg using `on_error` when the Cmd is emitted.
+    pub fn save<T, F, APP, MSG>(
+        &self,
+        key: &str,
+        value: &T,
+        on_error: F,
+    ) -> Cmd<APP, MSG>
+    where
+        T: Serialize,
+        F: Fn(StorageError) -> MSG + 'static,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let storage = self.clone();
+        let key = key.to_string();
+        let json = serde_json::to_string(value)
+            .map_err(|e| StorageError::Serialize(e.to_string()));
+        Cmd::new(move |program| {
+            let result =
+                json.clone().and_then(|json| storage.set_item(&key, &json));
+            if let Err(e) = result {
+                program.dispatch(on_error(e));
+            }
+        })
+    }
+
+    /// Creates a Cmd which removes the value of the key, the errors are
+    /// mapped into a msg using `on_error`
+    pub fn delete<F, APP, MSG>(&self, key: &str, on_error: F) -> Cmd<APP, MSG>
+    where
+        F: Fn(StorageError) -> MSG + 'static,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let storage = self.clone();
+        let key = key.to_string();
+        Cmd::new(move |program| {
+            if let Err(e) = storage.remove(&key) {
+                program.dispatch(on_error(e));
+            }
+        })
+    }
+
+    /// Creates a subscription which emits the msg returned from `f` with the
+    /// new value, whenever the value of the key is changed by the other tabs
+    /// or windows of the same app.
+    /// The new value is `Ok(None)` if it is removed.
+    pub fn on_change<T, F, MSG>(&self, key: &str, f: F) -> Sub<MSG>
+    where
+        T: DeserializeOwned,
+        F: Fn(Result<Option<T>, StorageError>) -> MSG + 'static,
+        MSG: 'static,
+    {
+        let storage = self.clone();
+        let key = self.namespaced_key(key);
+        let f = Rc::new(f);
+        let subscription_key = format!("storage:{:?}:{}", self.area, key);
+        let subscription = CustomSubscription::new(
+            subscription_key,
+            move |program: Rc<dyn Dispatch<MSG>>| {
+                let storage = storage.clone();
+                let key = key.clone();
+                let f = Rc::clone(&f);
+                let program_clone = Rc::clone(&program);
+                let closure: Closure<dyn FnMut(web_sys::Event)> =
+                    Closure::wrap(Box::new(move |event: web_sys::Event| {
+                        let event: StorageEvent = event.unchecked_into();
+                        let value = storage.changed_value(&event, &key);
+                        if let Some(value) = value {
+                            let value = value.map(|value| deserialize(&value));
+                            program_clone.dispatch(f(value.transpose()));
+                        }
+                    }));
+                let listener: Box<dyn Any> = Box::new(EventListener::attach(
+                    &*program,
+                    crate::window().into(),
+                    "storage",
+                    closure,
+                ));
+                listener
+            },
+        );
+        Sub::from(Subscription::Custom(subscription))
+    }
+
+    /// returns the new value if the storage event is a change of the key in
+    /// this storage area, clearing the storage is a change to `None`
+    fn changed_value(
+        &self,
+        event: &StorageEvent,
+        key: &str,
+    ) -> Option<Option<String>> {
+        let storage_area = event.storage_area()?;
+        if storage_area != self.web_storage().ok()? {
+            return None;
+        }
+        match event.key() {
+            Some(event_key) if event_key == key => Some(event.new_value()),
+            Some(_) => None,
+            None => Some(None),
+        }
+    }
+
+    fn set_item(&self, key: &str, json: &str) -> Result<(), StorageError> {
+        self.web_storage()?
+            .set_item(&self.namespaced_key(key), json)
+            .map_err(|e| StorageError::Write(error_message(e)))
+    }
+
+    fn web_storage(&self) -> Result<web_sys::Storage, StorageError> {
+        let window = crate::window();
+        let storage = match self.area {
+            StorageArea::Local => window.local_storage(),
+            StorageArea::Session => window.session_storage(),
+        };
+        storage
+            .map_err(|e| StorageError::Unavailable(error_message(e)))?
+            .ok_or_else(|| {
+                StorageError::Unavailable(format!("no {:?} storage", self.area))
+            })
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(json: &str) -> Result<T, StorageError> {
+    serde_json::from_str(json)
+        .map_err(|e| StorageError::Deserialize(e.to_string()))
+}
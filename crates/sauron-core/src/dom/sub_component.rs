@@ -0,0 +1,232 @@
+//! child components which keep their own state and are mounted in the view of
+//! the parent component
+//!
+use crate::{
+    dom::{
+        DomError,
+        Program,
+    },
+    html::{
+        attributes::{
+            attr,
+            key,
+        },
+        div,
+    },
+    Component,
+    Dispatch,
+    Node,
+};
+use std::{
+    any::Any,
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    rc::Rc,
+};
+use web_sys::Element;
+
+/// the attribute of the element where the sub component is mounted, the
+/// value is the id of the sub component
+pub(crate) const DATA_SAURON_COMPONENT: &str = "data-sauron-component";
+
+/// mounts the sub component into the element, with the program of the parent
+type MountFn<PMSG> = Rc<dyn Fn(&Element, Option<Rc<dyn Dispatch<PMSG>>>)>;
+
+thread_local! {
+    /// the `MountFn` of the sub components, keyed by their id
+    static MOUNTS: RefCell<HashMap<usize, Box<dyn Any>>> =
+        RefCell::new(HashMap::new());
+    static LAST_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A child `Component` which is embedded as a node in the view of the
+/// parent component.
+///
+/// The sub component keeps its own state and is run by its own `Program`
+/// which is mounted in the element placed by `view`. The msgs of the sub
+/// component only update its own state and re-render its own view, the
+/// parent view is not diffed at all. The parent can still receive the msgs of
+/// the sub component through the mapping function passed to `view`.
+///
+/// The `SubComponent` is kept in the state of the parent, such that the same
+/// sub component is placed in every view of the parent.
+///
+/// Example:
+/// ```rust,ignore
+/// struct App {
+///     counter: SubComponent<Counter, CounterMsg>,
+/// }
+///
+/// impl Component<Msg> for App {
+///     fn view(&self) -> Node<Msg> {
+///         div(vec![], vec![
+///             self.counter.view(|msg| match msg {
+///                 CounterMsg::Reset => Some(Msg::CounterReset),
+///                 _ => None,
+///             }),
+///         ])
+///     }
+/// }
+/// ```
+pub struct SubComponent<COMP, MSG>
+where
+    MSG: 'static,
+{
+    id: usize,
+    mount: Rc<Mount<COMP, MSG>>,
+}
+
+/// the app of the sub component, which is run by a program once it is mounted
+struct Mount<COMP, MSG>
+where
+    MSG: 'static,
+{
+    /// the app which is not yet mounted
+    app: RefCell<Option<COMP>>,
+    /// the program of the app once it is mounted
+    program: RefCell<Option<Program<COMP, MSG>>>,
+}
+
+impl<COMP, MSG> SubComponent<COMP, MSG>
+where
+    COMP: Component<MSG> + 'static,
+    MSG: 'static,
+{
+    /// create a sub component of this app, the app is mounted once the node
+    /// from `view` is created in the DOM
+    pub fn new(app: COMP) -> Self {
+        let id = LAST_ID.with(|last_id| {
+            last_id.set(last_id.get() + 1);
+            last_id.get()
+        });
+        SubComponent {
+            id,
+            mount: Rc::new(Mount {
+                app: RefCell::new(Some(app)),
+                program: RefCell::new(None),
+            }),
+        }
+    }
+
+    /// the program which runs the sub component, this is `None` until the
+    /// sub component is mounted
+    pub fn program(&self) -> Option<Program<COMP, MSG>> {
+        self.mount.program.borrow().clone()
+    }
+
+    /// The node to be placed in the view of the parent, where the sub
+    /// component is mounted. The msgs of the sub component are passed to
+    /// `map`, and the returned parent msgs are dispatched to the parent.
+    ///
+    /// The content of the node is managed by the sub component, so it is not
+    /// diffed with the view of the parent.
+    pub fn view<PMSG, F>(&self, map: F) -> Node<PMSG>
+    where
+        PMSG: 'static,
+        F: Fn(&MSG) -> Option<PMSG> + 'static,
+    {
+        let mount = Rc::clone(&self.mount);
+        let map = Rc::new(map);
+        let mount_fn: MountFn<PMSG> = Rc::new(move |element, parent| {
+            mount.mount_to(element, parent, Rc::clone(&map))
+        });
+        MOUNTS.with(|mounts| {
+            mounts.borrow_mut().insert(self.id, Box::new(mount_fn))
+        });
+        div(
+            vec![
+                key(format!("sauron-component-{}", self.id)),
+                attr(DATA_SAURON_COMPONENT, self.id),
+            ],
+            vec![],
+        )
+    }
+}
+
+impl<COMP, MSG> Drop for SubComponent<COMP, MSG>
+where
+    MSG: 'static,
+{
+    fn drop(&mut self) {
+        MOUNTS.with(|mounts| mounts.borrow_mut().remove(&self.id));
+        let program = self.mount.program.borrow_mut().take();
+        if let Some(program) = program {
+            program.stop();
+        }
+    }
+}
+
+impl<COMP, MSG> Mount<COMP, MSG>
+where
+    COMP: Component<MSG> + 'static,
+    MSG: 'static,
+{
+    /// mount the app into the element, if the app is already mounted
+    /// its view is moved into the element
+    fn mount_to<PMSG, F>(
+        &self,
+        element: &Element,
+        parent: Option<Rc<dyn Dispatch<PMSG>>>,
+        map: Rc<F>,
+    ) where
+        PMSG: 'static,
+        F: Fn(&MSG) -> Option<PMSG> + 'static,
+    {
+        let mounted = self.program.borrow().clone();
+        let program = match mounted {
+            Some(program) => {
+                if let Err(e) = element.append_child(&program.root_node()) {
+                    program.report_error(DomError::modify_tree("append", e));
+                }
+                program
+            }
+            None => {
+                let app = self
+                    .app
+                    .borrow_mut()
+                    .take()
+                    .expect("must have the app before it is mounted");
+                let program = Program::new_append_to_mount(app, element);
+                *self.program.borrow_mut() = Some(program.clone());
+                program
+            }
+        };
+        if let Some(parent) = parent {
+            program.on_msg(move |msg| {
+                if let Some(parent_msg) = map(msg) {
+                    parent.dispatch(parent_msg);
+                }
+            });
+        }
+    }
+}
+
+/// mount the sub component whose id is in the `data-sauron-component`
+/// attribute of this element, the parent is the program of the view where the
+/// element is created
+pub(crate) fn mount_sub_component<PMSG>(
+    element: &Element,
+    parent: Option<Rc<dyn Dispatch<PMSG>>>,
+) where
+    PMSG: 'static,
+{
+    let id = element
+        .get_attribute(DATA_SAURON_COMPONENT)
+        .and_then(|id| id.parse::<usize>().ok());
+    let mount_fn = id.and_then(|id| {
+        MOUNTS.with(|mounts| {
+            mounts
+                .borrow()
+                .get(&id)
+                .and_then(|mount_fn| mount_fn.downcast_ref::<MountFn<PMSG>>())
+                .cloned()
+        })
+    });
+    match mount_fn {
+        Some(mount_fn) => mount_fn(element, parent),
+        None => log::warn!("there is no sub component with id: {:?}", id),
+    }
+}
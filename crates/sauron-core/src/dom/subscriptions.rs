@@ -0,0 +1,386 @@
+//! keeps the listeners of the component subscriptions in the browser
+//!
+use crate::{
+    dom::{
+        util::js_error_message,
+        DomError,
+    },
+    sub::{
+        Sub,
+        Subscription,
+        SubscriptionKey,
+    },
+    Browser,
+    Dispatch,
+    Event,
+};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+};
+use web_sys::{
+    Element,
+    EventTarget,
+    HtmlAnchorElement,
+    MouseEvent,
+};
+
+/// The subscriptions which has an attached listener in the browser.
+/// The same subscription key could appear multiple times,
+/// as such the nth occurrence of the key is also part of the identity.
+pub struct ActiveSubscriptions<MSG> {
+    active: HashMap<(SubscriptionKey, usize), ActiveSubscription<MSG>>,
+}
+
+/// A subscription and its listener.
+/// The subscription is shared with the listener, so that the callback can be
+/// replaced by the new subscription without re-attaching the listener.
+struct ActiveSubscription<MSG> {
+    subscription: Rc<RefCell<Subscription<MSG>>>,
+    _listener: Listener,
+}
+
+/// The listener is detached from the browser when this is dropped
+enum Listener {
+    Event { _listener: EventListener },
+    Interval {
+        handle: i32,
+        _closure: Closure<dyn FnMut()>,
+    },
+    /// multiple listeners for one subscription
+    Batch { _listeners: Vec<Listener> },
+    /// the listener of a custom subscription
+    Custom { _listener: Box<dyn Any> },
+}
+
+/// An event listener which is removed from the target when dropped
+pub(crate) struct EventListener {
+    target: EventTarget,
+    event_name: &'static str,
+    closure: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl<MSG> ActiveSubscriptions<MSG>
+where
+    MSG: 'static,
+{
+    /// create with no active subscriptions
+    pub fn new() -> Self {
+        ActiveSubscriptions {
+            active: HashMap::new(),
+        }
+    }
+
+    /// the number of subscriptions with attached listeners
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// diff the active subscriptions with the new subscriptions,
+    /// listeners are attached for the added subscriptions, while the
+    /// listeners of the subscriptions that are no longer in `sub` are removed.
+    pub fn update<DSP>(&mut self, program: &DSP, sub: Sub<MSG>)
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let mut occurrences: HashMap<SubscriptionKey, usize> = HashMap::new();
+        let mut active = HashMap::new();
+        for subscription in sub.subscriptions {
+            let key = subscription.key();
+            let nth = occurrences.entry(key.clone()).or_insert(0);
+            let id = (key, *nth);
+            *nth += 1;
+            let active_sub = match self.active.remove(&id) {
+                Some(active_sub) => {
+                    *active_sub.subscription.borrow_mut() = subscription;
+                    active_sub
+                }
+                None => ActiveSubscription::listen(program, subscription),
+            };
+            active.insert(id, active_sub);
+        }
+        // the remaining are no longer subscribed and are dropped here
+        self.active = active;
+    }
+}
+
+impl<MSG> ActiveSubscription<MSG>
+where
+    MSG: 'static,
+{
+    fn listen<DSP>(program: &DSP, subscription: Subscription<MSG>) -> Self
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let key = subscription.key();
+        let subscription = Rc::new(RefCell::new(subscription));
+        let listener = match key {
+            SubscriptionKey::Window(event_name) => Self::listen_event(
+                program,
+                &subscription,
+                crate::window().into(),
+                event_name,
+            ),
+            SubscriptionKey::Document(event_name) => Self::listen_event(
+                program,
+                &subscription,
+                crate::document().into(),
+                event_name,
+            ),
+            SubscriptionKey::Interval(interval) => {
+                Self::listen_interval(program, &subscription, interval)
+            }
+            SubscriptionKey::UrlChange => {
+                Self::listen_url_change(program, &subscription)
+            }
+            SubscriptionKey::Custom(_) => match &*subscription.borrow() {
+                Subscription::Custom(custom) => {
+                    Listener::Custom {
+                        _listener: custom.start(Rc::new(program.clone())),
+                    }
+                }
+                _ => unreachable!("must be a custom subscription"),
+            },
+        };
+        ActiveSubscription {
+            subscription,
+            _listener: listener,
+        }
+    }
+
+    fn listen_event<DSP>(
+        program: &DSP,
+        subscription: &Rc<RefCell<Subscription<MSG>>>,
+        target: EventTarget,
+        event_name: &'static str,
+    ) -> Listener
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let program_clone = program.clone();
+        let subscription = Rc::clone(subscription);
+        let closure: Closure<dyn FnMut(web_sys::Event)> =
+            Closure::wrap(Box::new(move |event: web_sys::Event| {
+                let msg = match &*subscription.borrow() {
+                    Subscription::Window(_, cb)
+                    | Subscription::Document(_, cb) => {
+                        cb.emit(Event::from(event))
+                    }
+                    _ => unreachable!("must be an event subscription"),
+                };
+                program_clone.dispatch(msg);
+            }));
+        Listener::attach(program, target, event_name, closure)
+    }
+
+    fn listen_interval<DSP>(
+        program: &DSP,
+        subscription: &Rc<RefCell<Subscription<MSG>>>,
+        interval: i32,
+    ) -> Listener
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let program_clone = program.clone();
+        let subscription = Rc::clone(subscription);
+        let closure: Closure<dyn FnMut()> = Closure::wrap(Box::new(move || {
+            let msg = match &*subscription.borrow() {
+                Subscription::Interval(_, cb) => cb.emit(()),
+                _ => unreachable!("must be an interval subscription"),
+            };
+            program_clone.dispatch(msg);
+        }));
+        let handle = crate::window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                interval,
+            );
+        match handle {
+            Ok(handle) => {
+                Listener::Interval {
+                    handle,
+                    _closure: closure,
+                }
+            }
+            Err(e) => {
+                let error = DomError::SetInterval(js_error_message(e));
+                program.report_error(error);
+                // there is no interval to be cleared
+                Listener::Batch { _listeners: vec![] }
+            }
+        }
+    }
+
+    /// listens to `popstate` of the window for the url changes, and
+    /// intercepts the clicks on the links within the app, so they are
+    /// navigated without reloading the page
+    fn listen_url_change<DSP>(
+        program: &DSP,
+        subscription: &Rc<RefCell<Subscription<MSG>>>,
+    ) -> Listener
+    where
+        DSP: Dispatch<MSG> + Clone + 'static,
+    {
+        let program_clone = program.clone();
+        let program_click = program.clone();
+        let subscription = Rc::clone(subscription);
+        let popstate: Closure<dyn FnMut(web_sys::Event)> =
+            Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let msg = match &*subscription.borrow() {
+                    Subscription::UrlChange(cb) => cb.emit(Browser::get_url()),
+                    _ => unreachable!("must be a url change subscription"),
+                };
+                program_clone.dispatch(msg);
+            }));
+        let click: Closure<dyn FnMut(web_sys::Event)> =
+            Closure::wrap(Box::new(move |event: web_sys::Event| {
+                if let Some(url) = in_app_link(&event) {
+                    // the browser loads the url which can not be navigated
+                    // within the app
+                    match Browser::navigate(&url) {
+                        Ok(()) => event.prevent_default(),
+                        Err(error) => program_click.report_error(error),
+                    }
+                }
+            }));
+        Listener::Batch {
+            _listeners: vec![
+                Listener::attach(
+                    program,
+                    crate::window().into(),
+                    "popstate",
+                    popstate,
+                ),
+                Listener::attach(
+                    program,
+                    crate::document().into(),
+                    "click",
+                    click,
+                ),
+            ],
+        }
+    }
+}
+
+impl Listener {
+    fn attach<DSP, MSG>(
+        program: &DSP,
+        target: EventTarget,
+        event_name: &'static str,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Self
+    where
+        DSP: Dispatch<MSG> + ?Sized,
+    {
+        Listener::Event {
+            _listener: EventListener::attach(
+                program, target, event_name, closure,
+            ),
+        }
+    }
+}
+
+impl EventListener {
+    /// add the closure as the listener of the event in the target,
+    /// the error in adding the listener is reported to the program
+    pub(crate) fn attach<DSP, MSG>(
+        program: &DSP,
+        target: EventTarget,
+        event_name: &'static str,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    ) -> Self
+    where
+        DSP: Dispatch<MSG> + ?Sized,
+    {
+        let result = target.add_event_listener_with_callback(
+            event_name,
+            closure.as_ref().unchecked_ref(),
+        );
+        if let Err(e) = result {
+            program
+                .report_error(DomError::event_listener("add", event_name, e));
+        }
+        EventListener {
+            target,
+            event_name,
+            closure,
+        }
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let result = self.target.remove_event_listener_with_callback(
+            self.event_name,
+            self.closure.as_ref().unchecked_ref(),
+        );
+        if let Err(e) = result {
+            log::error!(
+                "{}",
+                DomError::event_listener("remove", self.event_name, e)
+            );
+        }
+    }
+}
+
+/// returns the url of the link which is clicked, if the link can be navigated
+/// within the app.
+/// The clicks with modifier keys, links to other origins, links which open in
+/// other targets or are downloads, and links to a fragment of the current page
+/// are left to the browser.
+fn in_app_link(event: &web_sys::Event) -> Option<String> {
+    let mouse_event: &MouseEvent = event.dyn_ref()?;
+    if event.default_prevented()
+        || mouse_event.button() != 0
+        || mouse_event.meta_key()
+        || mouse_event.ctrl_key()
+        || mouse_event.shift_key()
+        || mouse_event.alt_key()
+    {
+        return None;
+    }
+    let target: Element = event.target()?.dyn_into().ok()?;
+    let anchor: HtmlAnchorElement =
+        target.closest("a[href]").ok()??.dyn_into().ok()?;
+    let is_external = anchor
+        .get_attribute("rel")
+        .map(|rel| rel.split_whitespace().any(|rel| rel == "external"))
+        .unwrap_or(false);
+    let target = anchor.target();
+    if !(target.is_empty() || target == "_self")
+        || anchor.has_attribute("download")
+        || is_external
+    {
+        return None;
+    }
+    let location = crate::window().location();
+    if anchor.origin() != location.origin().ok()? {
+        return None;
+    }
+    let path = format!("{}{}", anchor.pathname(), anchor.search());
+    let current_path = format!(
+        "{}{}",
+        location.pathname().ok()?,
+        location.search().ok()?
+    );
+    let hash = anchor.hash();
+    if path == current_path && !hash.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", path, hash))
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // the other listeners are removed when their content is dropped
+        if let Listener::Interval { handle, .. } = self {
+            crate::window().clear_interval_with_handle(*handle);
+        }
+    }
+}
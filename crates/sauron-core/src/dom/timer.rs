@@ -0,0 +1,217 @@
+use crate::{dom::DomError, Cmd, Component, Dispatch};
+use std::{cell::RefCell, convert::TryFrom, fmt, rc::Rc, time::Duration};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// provides Cmds which dispatch msgs at a later time, such as
+/// delayed msgs, periodic msgs and msgs in the next animation frame.
+///
+/// Each of the Cmd comes with a handle which can be used to cancel it.
+/// The timers which can not be set in the browser are reported to the
+/// error hook of the `Program`.
+#[derive(Copy, Clone, Debug)]
+pub struct Timer;
+
+/// A handle to a timer Cmd, used for cancelling the timer.
+/// Dropping the handle doesn't cancel the timer.
+#[derive(Clone)]
+pub struct TimerHandle {
+    state: Rc<RefCell<TimerState>>,
+}
+
+enum TimerState {
+    /// the Cmd is not yet emitted
+    Pending,
+    /// the timer is set in the browser
+    Scheduled(ScheduledTimer),
+    /// the timer has already fired or has been cancelled
+    Done,
+}
+
+/// the id of the timer in the browser and its closure
+type ScheduledTimer = (TimerId, Closure<dyn FnMut()>);
+
+#[derive(Copy, Clone)]
+enum TimerId {
+    Timeout(i32),
+    Interval(i32),
+    AnimationFrame(i32),
+}
+
+impl Timer {
+    /// Creates a Cmd which dispatches the msg after the duration
+    pub fn after<APP, MSG>(
+        duration: Duration,
+        msg: MSG,
+    ) -> (Cmd<APP, MSG>, TimerHandle)
+    where
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        let msg = Rc::new(RefCell::new(Some(msg)));
+        Self::schedule(move |handle, program| {
+            let msg = Rc::clone(&msg);
+            let closure: Closure<dyn FnMut()> =
+                Closure::wrap(Box::new(move || {
+                    if let Some(msg) = msg.borrow_mut().take() {
+                        program.dispatch(msg);
+                    }
+                    handle.finish();
+                }));
+            let id = crate::window()
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    to_millis(duration),
+                )
+                .map_err(|e| DomError::timer("set timeout", e))?;
+            Ok((TimerId::Timeout(id), closure))
+        })
+    }
+
+    /// Creates a Cmd which dispatches the msg returned from `f`
+    /// every interval, until it is cancelled
+    pub fn every<F, APP, MSG>(
+        interval: Duration,
+        f: F,
+    ) -> (Cmd<APP, MSG>, TimerHandle)
+    where
+        F: Fn() -> MSG + Clone + 'static,
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        Self::schedule(move |handle, program| {
+            let f = f.clone();
+            let closure: Closure<dyn FnMut()> =
+                Closure::wrap(Box::new(move || {
+                    // an interval is only done when it is cancelled
+                    let _keep_alive = &handle;
+                    program.dispatch(f());
+                }));
+            let id = crate::window()
+                .set_interval_with_callback_and_timeout_and_arguments_0(
+                    closure.as_ref().unchecked_ref(),
+                    to_millis(interval),
+                )
+                .map_err(|e| DomError::timer("set interval", e))?;
+            Ok((TimerId::Interval(id), closure))
+        })
+    }
+
+    /// Creates a Cmd which dispatches the msg returned from `f`
+    /// in the next animation frame
+    pub fn on_animation_frame<F, APP, MSG>(
+        f: F,
+    ) -> (Cmd<APP, MSG>, TimerHandle)
+    where
+        F: Fn() -> MSG + Clone + 'static,
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        Self::schedule(move |handle, program| {
+            let f = f.clone();
+            let closure: Closure<dyn FnMut()> =
+                Closure::wrap(Box::new(move || {
+                    program.dispatch(f());
+                    handle.finish();
+                }));
+            let id = crate::window()
+                .request_animation_frame(closure.as_ref().unchecked_ref())
+                .map_err(|e| DomError::timer("request animation frame", e))?;
+            Ok((TimerId::AnimationFrame(id), closure))
+        })
+    }
+
+    /// Creates a Cmd which cancels the timer of this handle.
+    pub fn cancel<APP, MSG>(handle: &TimerHandle) -> Cmd<APP, MSG>
+    where
+        MSG: 'static,
+        APP: Component<MSG> + 'static,
+    {
+        let handle = handle.clone();
+        Cmd::new(move |program| {
+            if let Err(error) = handle.cancel() {
+                program.report_error(error);
+            }
+        })
+    }
+
+    /// The timer is set when the Cmd is emitted, unless it is already
+    /// cancelled. The closure of the timer has to keep a clone of the handle,
+    /// so it stays alive until the timer is done.
+    /// The timer which can not be set is done, and the error is reported.
+    fn schedule<APP, MSG, S>(set_timer: S) -> (Cmd<APP, MSG>, TimerHandle)
+    where
+        S: Fn(
+                TimerHandle,
+                Rc<dyn Dispatch<MSG>>,
+            ) -> Result<ScheduledTimer, DomError>
+            + 'static,
+        MSG: 'static,
+    {
+        let handle = TimerHandle {
+            state: Rc::new(RefCell::new(TimerState::Pending)),
+        };
+        let handle_clone = handle.clone();
+        let cmd = Cmd::new(move |program| {
+            if handle_clone.is_pending() {
+                match set_timer(handle_clone.clone(), Rc::clone(&program)) {
+                    Ok(scheduled) => {
+                        *handle_clone.state.borrow_mut() =
+                            TimerState::Scheduled(scheduled);
+                    }
+                    Err(error) => {
+                        handle_clone.finish();
+                        program.report_error(error);
+                    }
+                }
+            }
+        });
+        (cmd, handle)
+    }
+}
+
+/// the duration in milliseconds, as it is taken by the browser timers
+fn to_millis(duration: Duration) -> i32 {
+    i32::try_from(duration.as_millis()).unwrap_or(i32::MAX)
+}
+
+impl TimerHandle {
+    /// cancel the timer, the msg will no longer be dispatched.
+    /// It has no effect if the timer is already done.
+    pub fn cancel(&self) -> Result<(), DomError> {
+        if let TimerState::Scheduled((id, _closure)) = self.take() {
+            let window = crate::window();
+            match id {
+                TimerId::Timeout(id) => window.clear_timeout_with_handle(id),
+                TimerId::Interval(id) => window.clear_interval_with_handle(id),
+                TimerId::AnimationFrame(id) => window
+                    .cancel_animation_frame(id)
+                    .map_err(|e| DomError::timer("cancel animation frame", e))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// returns true if the timer has already fired or has been cancelled
+    pub fn is_done(&self) -> bool {
+        matches!(*self.state.borrow(), TimerState::Done)
+    }
+
+    fn is_pending(&self) -> bool {
+        matches!(*self.state.borrow(), TimerState::Pending)
+    }
+
+    /// the timer has fired, the closure is dropped here
+    fn finish(&self) {
+        self.take();
+    }
+
+    fn take(&self) -> TimerState {
+        std::mem::replace(&mut *self.state.borrow_mut(), TimerState::Done)
+    }
+}
+
+impl fmt::Debug for TimerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TimerHandle {{ is_done: {} }}", self.is_done())
+    }
+}
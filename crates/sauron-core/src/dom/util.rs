@@ -1,6 +1,7 @@
 use wasm_bindgen::{
     closure::Closure,
     JsCast,
+    JsValue,
 };
 
 /// utility function which returns the Window element
@@ -14,10 +15,11 @@ pub fn history() -> web_sys::History {
 }
 
 /// utility function which executes the agument closure in a request animation frame
-pub fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+/// returns the handle, which can be used for cancelling the request
+pub fn request_animation_frame(f: &Closure<dyn FnMut()>) -> i32 {
     window()
         .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame` OK");
+        .expect("should register `requestAnimationFrame` OK")
 }
 
 /// request animation frame and execute function
@@ -57,3 +59,14 @@ pub fn performance() -> web_sys::Performance {
 pub fn now() -> f64 {
     performance().now()
 }
+
+/// extract the message of a javascript error
+pub(crate) fn js_error_message(js_value: JsValue) -> String {
+    if let Some(error) = js_value.dyn_ref::<js_sys::Error>() {
+        String::from(error.message())
+    } else if let Some(message) = js_value.as_string() {
+        message
+    } else {
+        format!("{:?}", js_value)
+    }
+}
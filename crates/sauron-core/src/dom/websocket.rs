@@ -0,0 +1,476 @@
+//! provides a web socket connection which is opened by a subscription,
+//! and is reconnected with a backoff whenever the connection is lost.
+//!
+use crate::{
+    dom::DomError,
+    sub::{
+        CustomSubscription,
+        Subscription,
+    },
+    Cmd,
+    Component,
+    Dispatch,
+    Sub,
+};
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::{
+    any::Any,
+    cell::{
+        Cell,
+        RefCell,
+    },
+    fmt,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+};
+use web_sys::{
+    BinaryType,
+    CloseEvent,
+    MessageEvent,
+};
+
+/// A web socket connection to the url.
+/// The connection is opened when the subscription returned from `subscribe`
+/// is added in the component `subscriptions`, and is closed when it is
+/// no longer in the subscriptions.
+///
+/// Example:
+/// ```rust,ignore
+/// // in the component
+/// fn subscriptions(&self) -> Sub<Msg> {
+///     self.socket.subscribe(Msg::Socket)
+/// }
+/// // in the update
+/// Msg::Socket(event) => match event.json::<Price>() {
+///     Some(Ok(price)) => self.prices.push(price),
+///     _ => (),
+/// },
+/// Msg::Subscribe(symbol) => return self.socket.send_text(&symbol),
+/// ```
+#[derive(Clone)]
+pub struct WebSocket {
+    url: String,
+    backoff: Option<Backoff>,
+    state: Rc<RefCell<SocketState>>,
+}
+
+/// The events of the web socket connection
+#[derive(Debug, Clone, PartialEq)]
+pub enum SocketEvent {
+    /// the connection is opened
+    Open,
+    /// a message is received from the server
+    Message(SocketMessage),
+    /// the connection has an error, the details of the error are not
+    /// exposed by the browser
+    Error,
+    /// the connection is closed
+    Close {
+        /// the close code sent by the server
+        code: u16,
+        /// the reason sent by the server
+        reason: String,
+        /// whether the connection is closed cleanly
+        was_clean: bool,
+    },
+    /// the connection is lost, and is opened again after the delay
+    Reconnecting {
+        /// the number of reconnections since the connection is lost
+        attempt: u32,
+        /// the delay in milliseconds before reconnecting
+        delay: i32,
+    },
+}
+
+/// A message received from the server
+#[derive(Debug, Clone, PartialEq)]
+pub enum SocketMessage {
+    /// a text message
+    Text(String),
+    /// a binary message
+    Binary(Vec<u8>),
+}
+
+/// How long to wait before reconnecting, the delay is doubled after every
+/// failed attempt until it reaches the max delay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// the delay in milliseconds before the first reconnection
+    pub initial: i32,
+    /// the maximum delay in milliseconds
+    pub max: i32,
+    /// stop reconnecting after this number of attempts,
+    /// None to keep on reconnecting
+    pub max_attempts: Option<u32>,
+}
+
+struct SocketState {
+    /// the socket of the opened connection
+    socket: Option<web_sys::WebSocket>,
+    /// the messages which are sent before the connection is opened
+    outbox: Vec<SocketMessage>,
+}
+
+/// a callback of the web socket events
+type EventHandler = Closure<dyn FnMut(web_sys::Event)>;
+
+/// the id of the reconnect timeout and its callback
+type ReconnectTimeout = (i32, Closure<dyn FnMut()>);
+
+/// The connection which is started by the subscription, the callbacks of the
+/// web socket only have a weak reference to this, so the connection is closed
+/// when the subscription is dropped.
+struct Connection<MSG> {
+    socket: WebSocket,
+    program: Rc<dyn Dispatch<MSG>>,
+    f: Rc<dyn Fn(SocketEvent) -> MSG>,
+    attempt: Cell<u32>,
+    handlers: RefCell<Vec<EventHandler>>,
+    reconnect: RefCell<Option<ReconnectTimeout>>,
+}
+
+impl WebSocket {
+    /// create a web socket to the url, ie: `wss://example.com/prices`,
+    /// which is reconnected with the default `Backoff`
+    pub fn new(url: &str) -> Self {
+        WebSocket {
+            url: url.to_string(),
+            backoff: Some(Backoff::default()),
+            state: Rc::new(RefCell::new(SocketState {
+                socket: None,
+                outbox: vec![],
+            })),
+        }
+    }
+
+    /// reconnect with this backoff when the connection is lost
+    pub fn reconnect(mut self, backoff: Backoff) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// don't reconnect when the connection is lost
+    pub fn no_reconnect(mut self) -> Self {
+        self.backoff = None;
+        self
+    }
+
+    /// the url of this web socket
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// returns true if the connection is opened
+    pub fn is_open(&self) -> bool {
+        self.state
+            .borrow()
+            .socket
+            .as_ref()
+            .map(|socket| socket.ready_state() == web_sys::WebSocket::OPEN)
+            .unwrap_or(false)
+    }
+
+    /// Creates a subscription which opens the connection, the events of the
+    /// connection are mapped into msgs using `f`.
+    /// The connection is closed when the subscription is removed.
+    pub fn subscribe<F, MSG>(&self, f: F) -> Sub<MSG>
+    where
+        F: Fn(SocketEvent) -> MSG + 'static,
+        MSG: 'static,
+    {
+        let socket = self.clone();
+        let f: Rc<dyn Fn(SocketEvent) -> MSG> = Rc::new(f);
+        let subscription = CustomSubscription::new(
+            format!("websocket:{}", self.url),
+            move |program: Rc<dyn Dispatch<MSG>>| {
+                let connection = Rc::new(Connection {
+                    socket: socket.clone(),
+                    program,
+                    f: Rc::clone(&f),
+                    attempt: Cell::new(0),
+                    handlers: RefCell::new(vec![]),
+                    reconnect: RefCell::new(None),
+                });
+                connection.connect();
+                let listener: Box<dyn Any> = Box::new(connection);
+                listener
+            },
+        );
+        Sub::from(Subscription::Custom(subscription))
+    }
+
+    /// Creates a Cmd which sends the text to the server.
+    /// The messages which are sent while the connection is not opened are
+    /// sent once it is opened.
+    pub fn send_text<APP, MSG>(&self, text: &str) -> Cmd<APP, MSG>
+    where
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        self.send(SocketMessage::Text(text.to_string()))
+    }
+
+    /// Creates a Cmd which sends the bytes to the server as a binary message
+    pub fn send_binary<APP, MSG>(&self, bytes: &[u8]) -> Cmd<APP, MSG>
+    where
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        self.send(SocketMessage::Binary(bytes.to_vec()))
+    }
+
+    /// Creates a Cmd which sends the json serialization of the value to the
+    /// server as a text message.
+    ///
+    /// Returns the error if the value can not be serialized, nothing is sent
+    /// in that case
+    pub fn send_json<T, APP, MSG>(
+        &self,
+        value: &T,
+    ) -> Result<Cmd<APP, MSG>, serde_json::Error>
+    where
+        T: Serialize,
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let json = serde_json::to_string(value)?;
+        Ok(self.send(SocketMessage::Text(json)))
+    }
+
+    fn send<APP, MSG>(&self, message: SocketMessage) -> Cmd<APP, MSG>
+    where
+        APP: Component<MSG> + 'static,
+        MSG: 'static,
+    {
+        let socket = self.clone();
+        Cmd::new(move |_program| {
+            if socket.is_open() {
+                socket.send_now(&message);
+            } else {
+                socket.state.borrow_mut().outbox.push(message.clone());
+            }
+        })
+    }
+
+    /// send the message in the opened connection
+    fn send_now(&self, message: &SocketMessage) {
+        let state = self.state.borrow();
+        if let Some(socket) = &state.socket {
+            let result = match message {
+                SocketMessage::Text(text) => socket.send_with_str(text),
+                SocketMessage::Binary(bytes) => {
+                    socket.send_with_u8_array(bytes)
+                }
+            };
+            if let Err(e) = result {
+                log::error!("Unable to send in the web socket: {:?}", e);
+            }
+        }
+    }
+
+    /// send the messages which are queued while the connection is not opened
+    fn flush(&self) {
+        let outbox = std::mem::take(&mut self.state.borrow_mut().outbox);
+        for message in outbox.iter() {
+            self.send_now(message);
+        }
+    }
+}
+
+impl fmt::Debug for WebSocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "WebSocket {{ url: {:?}, is_open: {} }}",
+            self.url,
+            self.is_open()
+        )
+    }
+}
+
+impl SocketEvent {
+    /// decode the json of the message, returns None if this is not a message
+    pub fn json<T>(&self) -> Option<Result<T, serde_json::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            SocketEvent::Message(message) => Some(message.json()),
+            _ => None,
+        }
+    }
+}
+
+impl SocketMessage {
+    /// decode the json of this message
+    pub fn json<T>(&self) -> Result<T, serde_json::Error>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            SocketMessage::Text(text) => serde_json::from_str(text),
+            SocketMessage::Binary(bytes) => serde_json::from_slice(bytes),
+        }
+    }
+}
+
+impl Backoff {
+    /// the delay before the nth reconnection attempt which starts at 0,
+    /// returns None if there should be no more attempts
+    pub fn delay(&self, attempt: u32) -> Option<i32> {
+        if let Some(max_attempts) = self.max_attempts {
+            if attempt >= max_attempts {
+                return None;
+            }
+        }
+        let factor = 2_i32.checked_pow(attempt).unwrap_or(i32::MAX);
+        Some(self.initial.saturating_mul(factor).min(self.max))
+    }
+}
+
+impl Default for Backoff {
+    /// starts at half a second, up to 30 seconds, with no limit on attempts
+    fn default() -> Self {
+        Backoff {
+            initial: 500,
+            max: 30_000,
+            max_attempts: None,
+        }
+    }
+}
+
+impl<MSG> Connection<MSG>
+where
+    MSG: 'static,
+{
+    fn emit(&self, event: SocketEvent) {
+        self.program.dispatch((self.f)(event));
+    }
+
+    /// open the web socket, and attach the handlers of its events
+    fn connect(self: &Rc<Self>) {
+        let socket = match web_sys::WebSocket::new(&self.socket.url) {
+            Ok(socket) => socket,
+            Err(e) => {
+                log::error!("Unable to open the web socket: {:?}", e);
+                self.emit(SocketEvent::Error);
+                self.schedule_reconnect();
+                return;
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let onopen = self.handler(|connection, _event| {
+            connection.attempt.set(0);
+            connection.socket.flush();
+            connection.emit(SocketEvent::Open);
+        });
+        let onmessage = self.handler(|connection, event| {
+            let event: MessageEvent = event.unchecked_into();
+            let data = event.data();
+            let message = match data.as_string() {
+                Some(text) => SocketMessage::Text(text),
+                None => {
+                    SocketMessage::Binary(
+                        js_sys::Uint8Array::new(&data).to_vec(),
+                    )
+                }
+            };
+            connection.emit(SocketEvent::Message(message));
+        });
+        let onerror = self.handler(|connection, _event| {
+            connection.emit(SocketEvent::Error);
+        });
+        let onclose = self.handler(|connection, event| {
+            let event: CloseEvent = event.unchecked_into();
+            connection.socket.state.borrow_mut().socket = None;
+            connection.emit(SocketEvent::Close {
+                code: event.code(),
+                reason: event.reason(),
+                was_clean: event.was_clean(),
+            });
+            connection.schedule_reconnect();
+        });
+        socket.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(onclose.as_ref().unchecked_ref()));
+
+        self.socket.state.borrow_mut().socket = Some(socket);
+        *self.handlers.borrow_mut() = vec![onopen, onmessage, onerror, onclose];
+    }
+
+    /// create a closure which calls `f` while the connection is alive
+    fn handler<F>(self: &Rc<Self>, f: F) -> EventHandler
+    where
+        F: Fn(&Rc<Self>, web_sys::Event) + 'static,
+    {
+        let connection: Weak<Self> = Rc::downgrade(self);
+        Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(connection) = connection.upgrade() {
+                f(&connection, event);
+            }
+        }))
+    }
+
+    /// connect again after the delay of the backoff
+    fn schedule_reconnect(self: &Rc<Self>) {
+        let backoff = match self.socket.backoff {
+            Some(backoff) => backoff,
+            None => return,
+        };
+        let attempt = self.attempt.get();
+        let delay = match backoff.delay(attempt) {
+            Some(delay) => delay,
+            None => return,
+        };
+        self.attempt.set(attempt + 1);
+        self.emit(SocketEvent::Reconnecting {
+            attempt: attempt + 1,
+            delay,
+        });
+        let connection: Weak<Self> = Rc::downgrade(self);
+        let closure: Closure<dyn FnMut()> =
+            Closure::wrap(Box::new(move || {
+                if let Some(connection) = connection.upgrade() {
+                    connection.connect();
+                }
+            }));
+        let result = crate::window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                closure.as_ref().unchecked_ref(),
+                delay,
+            );
+        match result {
+            Ok(id) => *self.reconnect.borrow_mut() = Some((id, closure)),
+            Err(e) => {
+                self.program.report_error(DomError::timer("set timeout", e))
+            }
+        }
+    }
+}
+
+/// The subscription is removed, the connection is closed without reconnecting
+impl<MSG> Drop for Connection<MSG> {
+    fn drop(&mut self) {
+        if let Some((id, _closure)) = self.reconnect.borrow_mut().take() {
+            crate::window().clear_timeout_with_handle(id);
+        }
+        if let Some(socket) = self.socket.state.borrow_mut().socket.take() {
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onerror(None);
+            socket.set_onclose(None);
+            let _ = socket.close_with_code(1000);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    dom::created_node::create_closure_wrap, Attribute, Cmd, Component,
+    dom::{created_node::create_closure_wrap, events::Listener},
+    Attribute, Cmd, Component,
 };
 use std::fmt::Debug;
 use wasm_bindgen::{self, prelude::*, JsCast};
@@ -26,12 +27,9 @@ impl Window {
 
             for event_attr in event_listeners.iter() {
                 let event_str = event_attr.name();
-                for event_cb in event_attr.value() {
-                    let callback =
-                        event_cb.get_callback().expect("expecting a callback");
-
+                for listener in Listener::of_attribute(event_attr) {
                     let closure_wrap: Closure<dyn FnMut(web_sys::Event)> =
-                        create_closure_wrap(&program, &callback);
+                        create_closure_wrap(&program, listener);
                     window
                         .add_event_listener_with_callback(
                             event_str,
@@ -0,0 +1,301 @@
+//! patches which relocates the existing keyed nodes instead of recreating them
+use crate::{
+    diff,
+    html::attributes::AttributeValue,
+    mt_dom::{
+        patch::{
+            AppendChildren,
+            InsertNode,
+            RemoveNode,
+        },
+        NodeIdx,
+    },
+    Node,
+    Patch,
+    Tag,
+};
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    fmt,
+};
+
+/// the values of the `key` attribute of a node
+type Key<'a> = Vec<&'a AttributeValue>;
+
+/// A patch to the DOM, which is either one of the patches of `mt_dom`
+/// or a move of an existing keyed node into another location.
+#[derive(PartialEq)]
+pub enum DomPatch<'a, MSG> {
+    /// a patch from the `diff`
+    Patch(Patch<'a, MSG>),
+    /// relocate an existing keyed node
+    MoveNode(MoveNode<'a, MSG>),
+}
+
+/// Move the node at `node_idx` of the old tree into the `target`,
+/// then apply the `patches` to the moved node.
+#[derive(PartialEq)]
+pub struct MoveNode<'a, MSG> {
+    /// the tag of the node being moved
+    pub tag: Option<&'a Tag>,
+    /// the index of the node being moved in the old tree
+    pub node_idx: NodeIdx,
+    /// where the node is moved into
+    pub target: MoveTarget,
+    /// the patches to the moved node, the node indexes of these patches
+    /// starts at 0 from the moved node
+    pub patches: Vec<DomPatch<'a, MSG>>,
+}
+
+/// the location where the moved node is placed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveTarget {
+    /// insert the node before the node with this index in the old tree
+    InsertBefore(NodeIdx),
+    /// append the node to the children of the node with this index in the old tree
+    AppendTo(NodeIdx),
+}
+
+impl MoveTarget {
+    /// the index of the node in the old tree where the node is moved into
+    pub fn node_idx(&self) -> NodeIdx {
+        match self {
+            MoveTarget::InsertBefore(node_idx) => *node_idx,
+            MoveTarget::AppendTo(node_idx) => *node_idx,
+        }
+    }
+}
+
+impl<'a, MSG> DomPatch<'a, MSG> {
+    /// the index of the node in the old tree where this patch is applied to
+    pub fn node_idx(&self) -> NodeIdx {
+        match self {
+            DomPatch::Patch(patch) => patch.node_idx(),
+            DomPatch::MoveNode(move_node) => move_node.node_idx,
+        }
+    }
+
+    /// the tag of the node where this patch is applied to
+    pub fn tag(&self) -> Option<&Tag> {
+        match self {
+            DomPatch::Patch(patch) => patch.tag(),
+            DomPatch::MoveNode(move_node) => move_node.tag,
+        }
+    }
+}
+
+impl<'a, MSG> From<Patch<'a, MSG>> for DomPatch<'a, MSG> {
+    fn from(patch: Patch<'a, MSG>) -> Self {
+        DomPatch::Patch(patch)
+    }
+}
+
+impl<'a, MSG> From<MoveNode<'a, MSG>> for DomPatch<'a, MSG> {
+    fn from(move_node: MoveNode<'a, MSG>) -> Self {
+        DomPatch::MoveNode(move_node)
+    }
+}
+
+impl<'a, MSG> fmt::Debug for DomPatch<'a, MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DomPatch::Patch(patch) => patch.fmt(f),
+            DomPatch::MoveNode(move_node) => move_node.fmt(f),
+        }
+    }
+}
+
+impl<'a, MSG> fmt::Debug for MoveNode<'a, MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MoveNode")
+            .field("tag", &self.tag)
+            .field("node_idx", &self.node_idx)
+            .field("target", &self.target)
+            .field("patches", &self.patches)
+            .finish()
+    }
+}
+
+/// diff 2 nodes the same way as `diff`, except that a keyed node which is
+/// removed from one place and inserted into another, such as into a
+/// different parent, is moved instead of being recreated.
+///
+/// Only the nodes with a key which is unique in both of the old and new tree
+/// and with the same tag are moved, the rest are patched like in `diff`.
+///
+/// Note: only the removed nodes themselves are paired with the inserted or
+/// appended nodes. A keyed node inside a subtree which is removed, replaced or
+/// inserted, such as an item of a list which is replaced by a list with
+/// another tag, is still recreated.
+pub fn diff_with_moves<'a, MSG>(
+    old: &'a Node<MSG>,
+    new: &'a Node<MSG>,
+) -> Vec<DomPatch<'a, MSG>>
+where
+    MSG: 'static,
+{
+    let patches = diff(old, new);
+
+    let has_removed_nodes = patches
+        .iter()
+        .any(|patch| matches!(patch, Patch::RemoveNode(_)));
+    // the inserted nodes which have a key, only these can be moved
+    let inserted: Vec<(Key<'a>, &'a Node<MSG>)> = patches
+        .iter()
+        .flat_map(|patch| {
+            match patch {
+                Patch::InsertNode(InsertNode { node, .. }) => vec![*node],
+                Patch::AppendChildren(AppendChildren { children, .. }) => {
+                    children.iter().map(|(_, child)| *child).collect()
+                }
+                _ => vec![],
+            }
+        })
+        .filter_map(|node| Some((key_of(node)?, node)))
+        .collect();
+    if !has_removed_nodes || inserted.is_empty() {
+        return patches.into_iter().map(DomPatch::from).collect();
+    }
+
+    let old_nodes = flatten_nodes(old);
+    let new_nodes = flatten_nodes(new);
+
+    // the removed old nodes which have a key
+    let removed: Vec<(Key<'a>, NodeIdx)> = patches
+        .iter()
+        .filter_map(|patch| {
+            match patch {
+                Patch::RemoveNode(RemoveNode { node_idx, .. }) => {
+                    let key = key_of(old_nodes.get(*node_idx)?)?;
+                    Some((key, *node_idx))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+
+    // pair the removed old node and the inserted new node with the same key,
+    // the moves are keyed by the address of the inserted new node
+    let moves: HashMap<*const Node<MSG>, NodeIdx> = inserted
+        .into_iter()
+        .filter_map(|(key, new_node)| {
+            let (_, old_idx) = removed
+                .iter()
+                .find(|(removed_key, _)| *removed_key == key)?;
+            let is_unique = count_key(&old_nodes, &key) == 1
+                && count_key(&new_nodes, &key) == 1;
+            if is_unique && old_nodes[*old_idx].tag() == new_node.tag() {
+                Some((address_of(new_node), *old_idx))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let moved_from: HashSet<NodeIdx> = moves.values().copied().collect();
+
+    let is_moved_from = |node_idx: NodeIdx| moved_from.contains(&node_idx);
+    let moved_to =
+        |new_node: &Node<MSG>| moves.get(&address_of(new_node)).copied();
+    let move_node = |old_idx: NodeIdx, new_node: &'a Node<MSG>, target| {
+        let old_node = old_nodes[old_idx];
+        DomPatch::from(MoveNode {
+            tag: old_node.tag(),
+            node_idx: old_idx,
+            target,
+            patches: diff_with_moves(old_node, new_node),
+        })
+    };
+
+    let mut dom_patches = vec![];
+    for patch in patches {
+        match patch {
+            Patch::RemoveNode(RemoveNode { node_idx, .. })
+                if is_moved_from(node_idx) => {}
+            Patch::InsertNode(InsertNode { node_idx, node, .. })
+                if moved_to(node).is_some() =>
+            {
+                let old_idx = moved_to(node).expect("must be moved");
+                dom_patches.push(move_node(
+                    old_idx,
+                    node,
+                    MoveTarget::InsertBefore(node_idx),
+                ));
+            }
+            Patch::AppendChildren(AppendChildren {
+                tag,
+                node_idx,
+                children,
+            }) => {
+                // the children are appended in order, so the moved children
+                // splits the rest of the children into separate patches
+                let mut appended = vec![];
+                for (new_node_idx, child) in children {
+                    if let Some(old_idx) = moved_to(child) {
+                        if !appended.is_empty() {
+                            dom_patches.push(DomPatch::from(Patch::from(
+                                AppendChildren::new(
+                                    tag,
+                                    node_idx,
+                                    std::mem::take(&mut appended),
+                                ),
+                            )));
+                        }
+                        dom_patches.push(move_node(
+                            old_idx,
+                            child,
+                            MoveTarget::AppendTo(node_idx),
+                        ));
+                    } else {
+                        appended.push((new_node_idx, child));
+                    }
+                }
+                if !appended.is_empty() {
+                    dom_patches.push(DomPatch::from(Patch::from(
+                        AppendChildren::new(tag, node_idx, appended),
+                    )));
+                }
+            }
+            patch => dom_patches.push(DomPatch::from(patch)),
+        }
+    }
+    dom_patches
+}
+
+/// the nodes of this tree in depth first order, which is the same order
+/// as the node indexes used in the patches
+fn flatten_nodes<MSG>(node: &Node<MSG>) -> Vec<&Node<MSG>> {
+    let mut nodes = vec![];
+    flatten_nodes_recursive(node, &mut nodes);
+    nodes
+}
+
+fn flatten_nodes_recursive<'a, MSG>(
+    node: &'a Node<MSG>,
+    nodes: &mut Vec<&'a Node<MSG>>,
+) {
+    nodes.push(node);
+    for child in node.get_children().into_iter().flatten() {
+        flatten_nodes_recursive(child, nodes);
+    }
+}
+
+/// the values of the key of this node
+fn key_of<MSG>(node: &Node<MSG>) -> Option<Key<'_>> {
+    node.get_attribute_value(&"key")
+}
+
+/// the address of the node, which identifies the node in the new tree
+fn address_of<MSG>(node: &Node<MSG>) -> *const Node<MSG> {
+    node
+}
+
+/// the number of nodes which have this key
+fn count_key<MSG>(nodes: &[&Node<MSG>], key: &Key) -> usize {
+    nodes
+        .iter()
+        .filter(|node| key_of(node).as_ref() == Some(key))
+        .count()
+}
@@ -11,12 +11,18 @@ pub use mt_dom::{
 
 #[macro_use]
 pub mod attributes;
+mod lazy;
 pub mod tags;
 pub mod units;
 
 #[cfg(feature = "with-dom")]
 pub use crate::dom::events;
 
+pub(crate) use lazy::{
+    LazyScope,
+    MEMO,
+};
+pub use lazy::lazy;
 pub use tags::{
     commons::*,
     self_closing::*,
@@ -18,6 +18,12 @@ pub use special::{
 };
 pub use style::Style;
 pub use style_macro::*;
+pub use url::{
+    decode_uri_component,
+    encode_uri_component,
+    Query,
+    Url,
+};
 pub use value::Value;
 
 #[macro_use]
@@ -27,6 +33,7 @@ mod style_macro;
 mod attribute_value;
 mod special;
 mod style;
+mod url;
 mod value;
 
 /// create a style attribute
@@ -254,6 +261,8 @@ pub(crate) fn merge_plain_attributes_values(
                     Some(fvalue.to_string())
                 }
                 AttributeValue::Empty => None,
+                #[cfg(feature = "with-dom")]
+                AttributeValue::EventMapper(_) => None,
             }
         })
         .collect();
@@ -14,6 +14,10 @@ pub enum AttributeValue {
     Style(Vec<Style>),
     /// no value
     Empty,
+    /// maps the events into the value which the callback of a typed event
+    /// listener such as `on_click` takes, it is not rendered
+    #[cfg(feature = "with-dom")]
+    EventMapper(crate::dom::events::EventMapper),
 }
 
 impl AttributeValue {
@@ -0,0 +1,261 @@
+//! provides a url builder which encodes the path segments and the query,
+//! and a parser for the query string such as `location.search`.
+//!
+use super::Value;
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+/// A url with encoded path segments and query params, which can be used in
+/// `href` and in the navigation Cmds such as `Browser::push_url`.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::html::attributes::Url;
+///
+/// let url = Url::new("/users")
+///     .segment(42)
+///     .segment("posts & comments")
+///     .query("tag", "rust/wasm")
+///     .query("page", 2)
+///     .fragment("top");
+/// assert_eq!(
+///     url.to_string(),
+///     "/users/42/posts%20%26%20comments?tag=rust%2Fwasm&page=2#top"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Url {
+    path: String,
+    query: Query,
+    fragment: Option<String>,
+}
+
+/// The params of a query string, in the order they appear.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::html::attributes::Query;
+///
+/// let query = Query::from_search("?q=the+one%20ring&page=2");
+/// assert_eq!(query.get("q"), Some("the one ring"));
+/// assert_eq!(query.parse::<u32>("page"), Some(2));
+/// assert_eq!(query.to_string(), "q=the%20one%20ring&page=2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Query {
+    params: Vec<(String, String)>,
+}
+
+impl Url {
+    /// create a url with this path, the path is used as is,
+    /// use `segment` to add segments which need to be encoded
+    pub fn new(path: &str) -> Self {
+        Url {
+            path: path.to_string(),
+            query: Query::new(),
+            fragment: None,
+        }
+    }
+
+    /// append an encoded segment to the path
+    pub fn segment(mut self, segment: impl ToString) -> Self {
+        if !self.path.ends_with('/') {
+            self.path.push('/');
+        }
+        self.path
+            .push_str(&encode_uri_component(&segment.to_string()));
+        self
+    }
+
+    /// append an encoded param to the query
+    pub fn query(mut self, name: &str, value: impl ToString) -> Self {
+        self.query = self.query.append(name, value);
+        self
+    }
+
+    /// set the fragment of the url, which is the text after the `#` sign
+    pub fn fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(fragment.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        if !self.query.is_empty() {
+            write!(f, "?{}", self.query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", encode_uri_component(fragment))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Url> for Value {
+    fn from(url: Url) -> Self {
+        Value::String(url.to_string())
+    }
+}
+
+impl Query {
+    /// create an empty query
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// parse the query string, with or without the leading `?`,
+    /// ie: the `location.search` of the browser
+    pub fn from_search(search: &str) -> Self {
+        let params = search
+            .trim_start_matches('?')
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let mut kv = pair.splitn(2, '=');
+                let name = kv.next().unwrap_or("");
+                let value = kv.next().unwrap_or("");
+                (decode(name, true), decode(value, true))
+            })
+            .collect();
+        Query { params }
+    }
+
+    /// parse the `location.search` of the browser
+    #[cfg(feature = "with-dom")]
+    pub fn from_location() -> Self {
+        let search = crate::window()
+            .location()
+            .search()
+            .expect("must have a search");
+        Self::from_search(&search)
+    }
+
+    /// append a param to the query
+    pub fn append(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// the first value of the param with this name
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// all the values of the param with this name, ie: `?tag=a&tag=b`
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// parse the first value of the param with this name.
+    /// Returns None if there is no such param or if it can not be parsed
+    pub fn parse<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name)?.parse().ok()
+    }
+
+    /// returns true if there are no params
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// iterate over the name and value of the params
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// The query string without the leading `?`
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, "&")?;
+            }
+            write!(
+                f,
+                "{}={}",
+                encode_uri_component(name),
+                encode_uri_component(value)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Query> for Value {
+    fn from(query: Query) -> Self {
+        Value::String(format!("?{}", query))
+    }
+}
+
+/// encode the text to be used as a path segment, a query name or value,
+/// only the characters which are left as is in javascript's
+/// `encodeURIComponent` are not escaped.
+pub fn encode_uri_component(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// decode the `%XX` escapes of a path segment, a query name or value.
+/// The invalid escapes are left as is.
+pub fn decode_uri_component(input: &str) -> String {
+    decode(input, false)
+}
+
+/// decode the `%XX` escapes, the invalid escapes are left as is.
+/// In the query string, `+` is also decoded as a space.
+fn decode(input: &str, plus_as_space: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = input
+                    .get(i + 1..i + 3)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()));
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' if plus_as_space => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
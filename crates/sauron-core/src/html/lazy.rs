@@ -0,0 +1,199 @@
+//! memoized views, which are rebuilt only when their dependency changes
+//!
+use crate::{
+    html::attributes::attr,
+    Node,
+};
+use std::{
+    any::{
+        Any,
+        TypeId,
+    },
+    borrow::Borrow,
+    cell::RefCell,
+    collections::{
+        hash_map::DefaultHasher,
+        HashMap,
+    },
+    hash::{
+        Hash,
+        Hasher,
+    },
+    panic::Location,
+};
+
+/// the name of the attribute which identifies the cached node of a lazy node,
+/// the diffing of 2 nodes with the same memo value is skipped
+pub(crate) const MEMO: &str = "memo";
+
+thread_local! {
+    static CACHE: RefCell<LazyCache> = RefCell::new(LazyCache::default());
+}
+
+/// the views built by `lazy`, keyed by the hash of its call site and
+/// dependency
+#[derive(Default)]
+struct LazyCache {
+    /// the scope of the view which is being built, 0 if there is none
+    scope: usize,
+    /// the last scope created
+    last_scope: usize,
+    /// the memo of the last node built
+    last_memo: u64,
+    entries: HashMap<u64, Entry>,
+}
+
+struct Entry {
+    /// the scope of the view which last used this node
+    scope: usize,
+    /// true if the node is used in the latest view of its scope
+    used: bool,
+    /// the dependency which the node is built from, it is compared to the
+    /// dependency of `lazy` since 2 dependencies could have the same hash
+    dependency: Box<dyn Any>,
+    node: Box<dyn Any>,
+}
+
+/// Each `Program` builds its view in its own scope, so the cached views of a
+/// program are not dropped when the view of another program is built.
+/// The cached views of the scope are dropped together with the scope.
+#[derive(Debug, PartialEq)]
+pub(crate) struct LazyScope(usize);
+
+impl LazyScope {
+    /// create a new scope
+    pub(crate) fn new() -> Self {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.last_scope += 1;
+            LazyScope(cache.last_scope)
+        })
+    }
+
+    /// build the view in this scope, then drop the cached views of this scope
+    /// which are not used in the view
+    pub(crate) fn view<T>(&self, view: impl FnOnce() -> T) -> T {
+        let outer_scope = CACHE.with(|cache| {
+            std::mem::replace(&mut cache.borrow_mut().scope, self.0)
+        });
+        let node = view();
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.scope = outer_scope;
+            let scope = self.0;
+            cache
+                .entries
+                .retain(|_, entry| entry.scope != scope || entry.used);
+            for entry in cache.entries.values_mut() {
+                if entry.scope == scope {
+                    entry.used = false;
+                }
+            }
+        });
+        node
+    }
+
+    /// drop all the cached views of this scope
+    pub(crate) fn clear(&self) {
+        let scope = self.0;
+        // the cache is already destroyed when the thread is exiting
+        let _ = CACHE.try_with(|cache| {
+            cache
+                .borrow_mut()
+                .entries
+                .retain(|_, entry| entry.scope != scope)
+        });
+    }
+}
+
+impl Drop for LazyScope {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Creates a node which is built from the `view` closure only when the
+/// `dependency` has changed, otherwise the previously built node is reused
+/// and the diffing of the node and its descendants is skipped.
+///
+/// The view is identified by the call site of `lazy` and the dependency,
+/// so the closure must build the view from the dependency alone.
+/// This is useful for large subtrees such as the rows of a table, where only
+/// a few of the rows changes in an update.
+///
+/// ```rust
+/// use sauron_core::{html::*, Node};
+///
+/// fn view_row(cells: &[String]) -> Node<()> {
+///     lazy(cells, || {
+///         tr(
+///             vec![],
+///             cells.iter().map(|cell| td(vec![], vec![text(cell)])).collect::<Vec<_>>(),
+///         )
+///     })
+/// }
+/// ```
+///
+/// The views which are not used in the latest view of the `Program` are
+/// dropped from the cache, each program has its own cache.
+/// Outside of a `Program`, such as in `render_to_string`, nothing is cached
+/// and the view is built every time.
+#[track_caller]
+pub fn lazy<D, F, MSG>(dependency: &D, view: F) -> Node<MSG>
+where
+    D: Hash + PartialEq + ToOwned + ?Sized,
+    D::Owned: 'static,
+    F: FnOnce() -> Node<MSG>,
+    MSG: 'static,
+{
+    if CACHE.with(|cache| cache.borrow().scope) == 0 {
+        return view();
+    }
+
+    let mut hasher = DefaultHasher::new();
+    Location::caller().hash(&mut hasher);
+    TypeId::of::<MSG>().hash(&mut hasher);
+    dependency.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let cached = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let scope = cache.scope;
+        cache.entries.get_mut(&hash).and_then(|entry| {
+            let is_same_dependency = entry
+                .dependency
+                .downcast_ref::<D::Owned>()
+                .map(|cached| cached.borrow() == dependency)
+                .unwrap_or(false);
+            if !is_same_dependency {
+                return None;
+            }
+            entry.scope = scope;
+            entry.used = true;
+            entry.node.downcast_ref::<Node<MSG>>().cloned()
+        })
+    });
+
+    cached.unwrap_or_else(|| {
+        let node = view();
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            // each built node has its own memo, so a node which replaces
+            // the cached node of another dependency with the same hash is
+            // still diffed
+            cache.last_memo += 1;
+            let node = node.add_attributes(vec![attr(MEMO, cache.last_memo)]);
+            let scope = cache.scope;
+            cache.entries.insert(
+                hash,
+                Entry {
+                    scope,
+                    used: true,
+                    dependency: Box::new(dependency.to_owned()),
+                    node: Box::new(node.clone()),
+                },
+            );
+            node
+        })
+    })
+}
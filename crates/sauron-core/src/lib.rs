@@ -36,8 +36,25 @@ pub mod svg;
 #[macro_use]
 pub mod jss;
 mod render;
-
-pub use render::Render;
+mod dom_patch;
+pub mod cmd;
+mod component;
+mod dispatch;
+mod router;
+pub mod sub;
+mod test_program;
+
+pub use cmd::Cmd;
+pub use component::Component;
+pub use dispatch::{Dispatch, MsgQueue};
+pub use dom_patch::{diff_with_moves, DomPatch, MoveNode, MoveTarget};
+pub use router::{RouteParams, Router};
+pub use sub::Sub;
+pub use test_program::{FireEventError, Selector, TestProgram};
+pub use render::{
+    render_to_string_with_state, render_with_state, ChunkWriter, HtmlDocument,
+    Render, RenderOptions, StreamRenderer, SERIALIZED_STATE_ID,
+};
 
 use html::attributes::AttributeValue;
 
@@ -108,12 +125,21 @@ where
 
     // check if the skip attribute is true
     // if it is true, skip diffing and no patches is created at this dom
-    let skip = |_old_node: &'a Node<MSG>, new_node: &'a Node<MSG>| {
-        new_node
-            .get_value("skip")
-            .map(|v| v.as_bool())
-            .flatten()
-            .unwrap_or(false)
+    // the lazy nodes built from the same dependency are not diffed
+    let skip = |old_node: &'a Node<MSG>, new_node: &'a Node<MSG>| {
+        let is_same_memo = match (
+            old_node.get_value(html::MEMO),
+            new_node.get_value(html::MEMO),
+        ) {
+            (Some(old_memo), Some(new_memo)) => old_memo == new_memo,
+            _ => false,
+        };
+        is_same_memo
+            || new_node
+                .get_value("skip")
+                .map(|v| v.as_bool())
+                .flatten()
+                .unwrap_or(false)
     };
 
     // check if the replace attribute evaluates to true,
@@ -1,37 +1,99 @@
 //! This contains a trait to be able to render
 //! virtual dom into a writable buffer
 //!
-use crate::{html::attributes, Attribute, Element, Node};
+use crate::{
+    html::{attributes, MEMO},
+    Attribute, Element, Node,
+};
+use serde::Serialize;
 use std::fmt;
 
+mod document;
+mod options;
+mod stream;
+
+pub(crate) use document::DATA_SAURON_STYLE;
+pub use document::HtmlDocument;
+pub use options::RenderOptions;
+pub use stream::{ChunkWriter, StreamRenderer};
+
+/// the elements where the text is not escaped, as in the html serialization
+/// algorithm
+const RAW_TEXT_ELEMENTS: [&str; 7] = [
+    "style", "script", "xmp", "iframe", "noembed", "noframes", "plaintext",
+];
+
+/// the elements which are phrasing content, a new line in between these
+/// elements would be displayed as a space
+const PHRASING_ELEMENTS: [&str; 46] = [
+    "a", "abbr", "audio", "b", "bdi", "bdo", "br", "button", "canvas", "cite",
+    "code", "data", "datalist", "del", "dfn", "em", "embed", "i", "iframe",
+    "img", "input", "ins", "kbd", "label", "map", "mark", "meter", "object",
+    "output", "picture", "progress", "q", "s", "samp", "select", "small",
+    "span", "strong", "sub", "sup", "textarea", "time", "u", "var", "video",
+    "wbr",
+];
+
+/// the elements where the whitespace is preserved, a new line or an
+/// indentation in any of their descendants would change the displayed text
+const PREFORMATTED_ELEMENTS: [&str; 2] = ["pre", "textarea"];
+
+/// the attributes which are true when they are present in the element, as
+/// specified in html
+const BOOLEAN_ATTRIBUTES: [&str; 25] = [
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// The id of the script element which contains the serialized state of the app,
+/// rendered by `render_with_state`
+pub const SERIALIZED_STATE_ID: &str = "sauron-serialized-state";
+
 /// render node, elements to a writable buffer
 pub trait Render {
-    // ISSUE: sublte difference in `render` and `render_to_string`:
-    //  - flow content element such as span will treat the whitespace in between them as html text
-    //  node
-    //  Example:
-    //  in `render`
-    //  ```html
-    //     <span>hello</span>
-    //     <span> world</span>
-    //  ```
-    //     will displayed as "hello  world"
-    //
-    //  where us `render_to_string`
-    //  ```html
-    //  <span>hello</span><span> world</span>
-    //  ```
-    //  will result to a desirable output: "hello world"
-    //
-    /// render the node to a writable buffer
+    /// render the node to a writable buffer, pretty printed with the
+    /// default `RenderOptions`
     fn render(&self, buffer: &mut dyn fmt::Write) -> fmt::Result {
-        self.render_with_indent(buffer, 0, &mut Some(0), false)
+        self.render_with_options(buffer, &RenderOptions::default())
     }
 
     /// no new_lines, no indents
     fn render_compressed(&self, buffer: &mut dyn fmt::Write) -> fmt::Result {
-        self.render_with_indent(buffer, 0, &mut Some(0), true)
+        self.render_with_options(buffer, &RenderOptions::compressed())
+    }
+
+    /// render the node to a writable buffer, formatted with the options
+    fn render_with_options(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        options: &RenderOptions,
+    ) -> fmt::Result {
+        self.render_with_indent(buffer, 0, &mut Some(0), options)
     }
+
     /// render instance to a writable buffer with indention
     /// node_idx is for debugging purposes
     fn render_with_indent(
@@ -39,7 +101,7 @@ pub trait Render {
         buffer: &mut dyn fmt::Write,
         indent: usize,
         node_idx: &mut Option<usize>,
-        compressed: bool,
+        options: &RenderOptions,
     ) -> fmt::Result;
 
     /// render compressed html to string
@@ -56,13 +118,13 @@ impl<MSG> Render for Node<MSG> {
         buffer: &mut dyn fmt::Write,
         indent: usize,
         node_idx: &mut Option<usize>,
-        compressed: bool,
+        options: &RenderOptions,
     ) -> fmt::Result {
         match self {
             Node::Element(element) => {
-                element.render_with_indent(buffer, indent, node_idx, compressed)
+                element.render_with_indent(buffer, indent, node_idx, options)
             }
-            Node::Text(text) => write!(buffer, "{}", text.text),
+            Node::Text(text) => render_text::<MSG>(None, &text.text, buffer),
         }
     }
 }
@@ -92,85 +154,206 @@ impl<MSG> Render for Element<MSG> {
         buffer: &mut dyn fmt::Write,
         indent: usize,
         node_idx: &mut Option<usize>,
-        compressed: bool,
+        options: &RenderOptions,
     ) -> fmt::Result {
-        write!(buffer, "<{}", self.tag())?;
-
-        let ref_attrs: Vec<&Attribute<MSG>> =
-            self.get_attributes().iter().map(|att| att).collect();
-        let merged_attributes: Vec<Attribute<MSG>> =
-            mt_dom::merge_attributes_of_same_name(&ref_attrs);
-
-        for attr in &merged_attributes {
-            // dont render empty attribute
-            // TODO: must check the attribute value for empty value
-            if !attr.name().is_empty() {
-                write!(buffer, " ")?;
-                attr.render_with_indent(buffer, indent, node_idx, compressed)?;
+        let merged_attributes = merge_attributes(self);
+        render_start_tag(
+            self,
+            &merged_attributes,
+            buffer,
+            indent,
+            node_idx,
+            options,
+        )?;
+
+        // the whitespace is preserved in `pre`, so all of its descendants
+        // are rendered without new lines and indentation
+        let preformatted_options;
+        let child_options = if PREFORMATTED_ELEMENTS.contains(self.tag())
+            && !options.is_compressed()
+        {
+            preformatted_options = options.preformatted();
+            &preformatted_options
+        } else {
+            options
+        };
+
+        // the children are kept in one line if a new line in between them
+        // would be displayed as a space, or if the whitespace is preserved
+        let children = self.get_children();
+        let is_inline = child_options.is_compressed()
+            || is_phrasing_element(self)
+            || children.iter().any(is_phrasing_content);
+
+        for child in children {
+            if let Some(idx) = node_idx.as_mut() {
+                *idx += 1;
+            }
+            if !is_inline {
+                options.new_line(buffer, indent + 1)?;
             }
+            render_child(
+                self,
+                child,
+                buffer,
+                indent + 1,
+                node_idx,
+                child_options,
+            )?;
         }
 
-        #[cfg(feature = "with-nodeidx-debug")]
-        if let Some(node_idx_) = node_idx {
-            let node_idx_attr: Attribute<MSG> =
-                crate::prelude::attr("node_idx", *node_idx_);
-            write!(buffer, " ")?;
-            node_idx_attr
-                .render_with_indent(buffer, indent, node_idx, compressed)?;
+        if !is_inline && !children.is_empty() {
+            options.new_line(buffer, indent)?;
         }
-        if self.self_closing {
-            write!(buffer, "/>")?;
-        } else {
-            write!(buffer, ">")?;
+
+        render_end_tag(self, &merged_attributes, buffer)
+    }
+}
+
+/// true if the element is one of the phrasing elements
+fn is_phrasing_element<MSG>(element: &Element<MSG>) -> bool {
+    PHRASING_ELEMENTS.contains(element.tag())
+}
+
+/// true if the node is a text or a phrasing element
+fn is_phrasing_content<MSG>(node: &Node<MSG>) -> bool {
+    match node {
+        Node::Text(_) => true,
+        Node::Element(element) => is_phrasing_element(element),
+    }
+}
+
+/// render the child node of the element, the text inside a raw text element
+/// such as `script` is not escaped
+fn render_child<MSG>(
+    element: &Element<MSG>,
+    child: &Node<MSG>,
+    buffer: &mut dyn fmt::Write,
+    indent: usize,
+    node_idx: &mut Option<usize>,
+    options: &RenderOptions,
+) -> fmt::Result {
+    match child {
+        Node::Text(text) => render_text(Some(element), &text.text, buffer),
+        Node::Element(_) => {
+            child.render_with_indent(buffer, indent, node_idx, options)
         }
+    }
+}
 
-        let children = self.get_children();
-        let first_child = children.get(0);
-        let is_first_child_text_node =
-            first_child.map(|node| node.is_text()).unwrap_or(false);
-
-        let is_lone_child_text_node =
-            children.len() == 1 && is_first_child_text_node;
-
-        // do not indent if it is only text child node
-        if is_lone_child_text_node {
-            node_idx.as_mut().map(|idx| *idx += 1);
-            first_child
-                .unwrap()
-                .render_with_indent(buffer, indent, node_idx, compressed)?;
-        } else {
-            // otherwise print all child nodes with each line and indented
-            for child in self.get_children() {
-                node_idx.as_mut().map(|idx| *idx += 1);
-                if !compressed {
-                    write!(buffer, "\n{}", "    ".repeat(indent + 1))?;
-                }
-                child.render_with_indent(
-                    buffer,
-                    indent + 1,
-                    node_idx,
-                    compressed,
-                )?;
-            }
+/// render the text which is a child of the `parent` element
+fn render_text<MSG>(
+    parent: Option<&Element<MSG>>,
+    text: &str,
+    buffer: &mut dyn fmt::Write,
+) -> fmt::Result {
+    match parent {
+        Some(parent) if RAW_TEXT_ELEMENTS.contains(parent.tag()) => {
+            write_raw_text(buffer, parent.tag(), text)
         }
+        _ => write_escaped(buffer, text, false),
+    }
+}
+
+/// the attributes of the element, where the values of the attributes with the
+/// same name are merged into one attribute.
+/// The memo of a lazy node is only used in diffing, and is not rendered.
+fn merge_attributes<MSG>(element: &Element<MSG>) -> Vec<Attribute<MSG>> {
+    let ref_attrs: Vec<&Attribute<MSG>> = element
+        .get_attributes()
+        .iter()
+        .filter(|att| *att.name() != MEMO)
+        .collect();
+    mt_dom::merge_attributes_of_same_name(&ref_attrs)
+}
 
-        // do not make a new line it if is only a text child node or it has no child nodes
-        if !is_lone_child_text_node && !children.is_empty() {
-            if !compressed {
-                write!(buffer, "\n{}", "    ".repeat(indent))?;
+/// render the opening tag of the element together with its attributes, the
+/// attributes are wrapped into their own lines if the tag is too long
+fn render_start_tag<MSG>(
+    element: &Element<MSG>,
+    merged_attributes: &[Attribute<MSG>],
+    buffer: &mut dyn fmt::Write,
+    indent: usize,
+    node_idx: &mut Option<usize>,
+    options: &RenderOptions,
+) -> fmt::Result {
+    let mut sorted_attributes: Vec<&Attribute<MSG>> =
+        merged_attributes.iter().collect();
+    if options.is_sort_attributes() {
+        sorted_attributes.sort_by_key(|attr| *attr.name());
+    }
+    let mut rendered_attributes = vec![];
+    for attr in sorted_attributes {
+        // dont render empty attribute
+        // TODO: must check the attribute value for empty value
+        if !attr.name().is_empty() {
+            let mut rendered = String::new();
+            attr.render_with_indent(&mut rendered, indent, node_idx, options)?;
+            if !rendered.is_empty() {
+                rendered_attributes.push(rendered);
             }
         }
+    }
 
-        let inner_html = extract_inner_html(&merged_attributes);
-        if !inner_html.is_empty() {
-            write!(buffer, "{}", inner_html)?;
-        }
+    #[cfg(feature = "with-nodeidx-debug")]
+    if let Some(node_idx_) = node_idx {
+        let node_idx_attr: Attribute<MSG> =
+            crate::prelude::attr("node_idx", *node_idx_);
+        let mut rendered = String::new();
+        node_idx_attr.render_with_indent(
+            &mut rendered,
+            indent,
+            node_idx,
+            options,
+        )?;
+        rendered_attributes.push(rendered);
+    }
 
-        if !self.self_closing {
-            write!(buffer, "</{}>", self.tag())?;
+    let tag_end = if element.self_closing {
+        options.self_closing_end()
+    } else {
+        ">"
+    };
+    let tag_width = 1
+        + element.tag().chars().count()
+        + rendered_attributes
+            .iter()
+            .map(|attr| 1 + attr.chars().count())
+            .sum::<usize>()
+        + tag_end.len();
+    let is_wrapped = !rendered_attributes.is_empty()
+        && options.is_too_long(indent, tag_width);
+
+    write!(buffer, "<{}", element.tag())?;
+    for attr in rendered_attributes {
+        if is_wrapped {
+            options.new_line(buffer, indent + 1)?;
+        } else {
+            write!(buffer, " ")?;
         }
-        Ok(())
+        write!(buffer, "{}", attr)?;
+    }
+    if is_wrapped {
+        options.new_line(buffer, indent)?;
     }
+    write!(buffer, "{}", tag_end)
+}
+
+/// render the inner html of the element, followed by its closing tag
+fn render_end_tag<MSG>(
+    element: &Element<MSG>,
+    merged_attributes: &[Attribute<MSG>],
+    buffer: &mut dyn fmt::Write,
+) -> fmt::Result {
+    let inner_html = extract_inner_html(merged_attributes);
+    if !inner_html.is_empty() {
+        write!(buffer, "{}", inner_html)?;
+    }
+
+    if !element.self_closing {
+        write!(buffer, "</{}>", element.tag())?;
+    }
+    Ok(())
 }
 
 impl<MSG> Render for Attribute<MSG> {
@@ -179,19 +362,139 @@ impl<MSG> Render for Attribute<MSG> {
         buffer: &mut dyn fmt::Write,
         _indent: usize,
         _node_idx: &mut Option<usize>,
-        _compressed: bool,
+        _options: &RenderOptions,
     ) -> fmt::Result {
         let (_callbacks, plain_values, _func_values) =
             attributes::partition_callbacks_from_plain_and_func_calls(&self);
+        // a boolean attribute is true when it is present, regardless of its
+        // value, so it is rendered without a value or it is left out if false
+        if BOOLEAN_ATTRIBUTES.contains(self.name()) {
+            let is_false = plain_values.iter().all(|att_value| {
+                att_value.get_simple().and_then(|v| v.as_bool()) == Some(false)
+            });
+            if !is_false {
+                write!(buffer, "{}", self.name())?;
+            }
+            return Ok(());
+        }
         if let Some(merged_plain_values) =
             attributes::merge_plain_attributes_values(&plain_values)
         {
-            write!(buffer, "{}=\"{}\"", self.name(), merged_plain_values)?;
+            write!(buffer, "{}=\"", self.name())?;
+            write_escaped(buffer, &merged_plain_values, true)?;
+            write!(buffer, "\"")?;
         }
         Ok(())
     }
 }
 
+/// write the text with its characters escaped the same way as in the html
+/// serialization algorithm, the attribute value is written inside double quotes
+fn write_escaped(
+    buffer: &mut dyn fmt::Write,
+    text: &str,
+    in_attribute: bool,
+) -> fmt::Result {
+    let mut unescaped = 0;
+    for (i, ch) in text.char_indices() {
+        let escaped = match ch {
+            '&' => "&amp;",
+            '\u{a0}' => "&nbsp;",
+            '"' if in_attribute => "&quot;",
+            '<' if !in_attribute => "&lt;",
+            '>' if !in_attribute => "&gt;",
+            _ => continue,
+        };
+        buffer.write_str(&text[unescaped..i])?;
+        buffer.write_str(escaped)?;
+        unescaped = i + ch.len_utf8();
+    }
+    buffer.write_str(&text[unescaped..])
+}
+
+/// write the text of a raw text element as is, except the closing tag of the
+/// element such as `</script`, which would end the element early, is written
+/// as `<\/script`. This is still the same string in javascript and css, while
+/// the other raw text elements such as `iframe` show the text with the `\`.
+fn write_raw_text(
+    buffer: &mut dyn fmt::Write,
+    tag: &str,
+    text: &str,
+) -> fmt::Result {
+    let mut unescaped = 0;
+    for (i, _) in text.match_indices("</") {
+        let closes_element = text
+            .get(i + 2..i + 2 + tag.len())
+            .map(|name| name.eq_ignore_ascii_case(tag))
+            .unwrap_or(false);
+        if closes_element {
+            buffer.write_str(&text[unescaped..i])?;
+            buffer.write_str("<\\/")?;
+            unescaped = i + 2;
+        }
+    }
+    buffer.write_str(&text[unescaped..])
+}
+
+/// render the view together with the serialized state of the app, which is contained in a
+/// `<script type="application/json">` element right next to the view.
+/// This allows the client to resume the app from the same state the server used
+/// in rendering the view, using `Program::resume`
+pub fn render_with_state<MSG, STATE>(
+    view: &Node<MSG>,
+    state: &STATE,
+    buffer: &mut dyn fmt::Write,
+) -> fmt::Result
+where
+    STATE: Serialize,
+{
+    let json = serde_json::to_string(state).map_err(|e| {
+        log::error!("unable to serialize the app state: {}", e);
+        fmt::Error
+    })?;
+    view.render_compressed(buffer)?;
+    buffer.write_str(&state_script(&json))
+}
+
+/// render the view together with the serialized state of the app into a string,
+/// the error in serializing the state is returned
+pub fn render_to_string_with_state<MSG, STATE>(
+    view: &Node<MSG>,
+    state: &STATE,
+) -> Result<String, serde_json::Error>
+where
+    STATE: Serialize,
+{
+    let json = serde_json::to_string(state)?;
+    let mut buffer = view.render_to_string();
+    buffer.push_str(&state_script(&json));
+    Ok(buffer)
+}
+
+/// the script element which contains the serialized state of the app
+fn state_script(json: &str) -> String {
+    format!(
+        "<script type=\"application/json\" id=\"{}\">{}</script>",
+        SERIALIZED_STATE_ID,
+        escape_json_in_script(json)
+    )
+}
+
+/// escape the characters which could close the script element or start a comment in it,
+/// the escaped json still parse to the same value.
+fn escape_json_in_script(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for ch in json.chars() {
+        match ch {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -221,4 +524,189 @@ mod test {
         view.render(&mut buffer).expect("must render");
         assert_eq!(expected, buffer);
     }
+
+    #[test]
+    fn test_render_with_state() {
+        let view: Node<()> = div(vec![class("app")], vec![text("hello")]);
+        let state = serde_json::json!({
+            "name": "</script><script>alert('xss')</script>",
+            "count": 1,
+        });
+        let expected = r#"<div class="app">hello</div><script type="application/json" id="sauron-serialized-state">{"name":"\u003c/script\u003e\u003cscript\u003ealert('xss')\u003c/script\u003e","count":1}</script>"#;
+        let rendered = render_to_string_with_state(&view, &state)
+            .expect("must serialize");
+        assert_eq!(expected, rendered);
+
+        let json = rendered
+            .trim_start_matches(r#"<div class="app">hello</div><script type="application/json" id="sauron-serialized-state">"#)
+            .trim_end_matches("</script>");
+        let resumed: serde_json::Value =
+            serde_json::from_str(json).expect("must be a valid json");
+        assert_eq!(state, resumed);
+    }
+
+    #[test]
+    fn test_render_with_state_which_can_not_be_serialized() {
+        let view: Node<()> = div(vec![], vec![]);
+        let state: std::collections::HashMap<(i32, i32), i32> =
+            vec![((0, 0), 1)].into_iter().collect();
+        assert!(render_to_string_with_state(&view, &state).is_err());
+        let mut buffer = String::new();
+        assert!(render_with_state(&view, &state, &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_render_escaped_text_and_attributes() {
+        let view: Node<()> = div(
+            vec![attr("title", r#"a "quoted" <b> & c"#)],
+            vec![text("<script>alert('xss')</script> & \u{a0}")],
+        );
+        let expected = r#"<div title="a &quot;quoted&quot; <b> &amp; c">&lt;script&gt;alert('xss')&lt;/script&gt; &amp; &nbsp;</div>"#;
+        assert_eq!(expected, view.render_to_string());
+    }
+
+    #[test]
+    fn test_render_raw_text_elements() {
+        let view: Node<()> = div(
+            vec![],
+            vec![
+                script(
+                    vec![],
+                    vec![text("if (a < b && c) { s = '</SCRIPT>' }")],
+                ),
+                html::tags::style(
+                    vec![],
+                    vec![text("a > b { content: '&'; }")],
+                ),
+                html_element("xmp", vec![], vec![text("</xmp><b>&</b>")]),
+            ],
+        );
+        let expected = r#"<div><script>if (a < b && c) { s = '<\/SCRIPT>' }</script><style>a > b { content: '&'; }</style><xmp><\/xmp><b>&</b></xmp></div>"#;
+        assert_eq!(expected, view.render_to_string());
+    }
+
+    #[test]
+    fn test_render_inner_html_is_not_escaped() {
+        let view: Node<()> = div(vec![inner_html("<b>bold</b>")], vec![]);
+        assert_eq!("<div><b>bold</b></div>", view.render_to_string());
+    }
+
+    #[test]
+    fn test_render_keeps_phrasing_content_inline() {
+        let view: Node<()> = div(
+            vec![],
+            vec![
+                p(
+                    vec![],
+                    vec![
+                        span(vec![], vec![text("hello")]),
+                        span(vec![], vec![text(" world")]),
+                    ],
+                ),
+                ul(vec![], vec![li(vec![], vec![text("item")])]),
+                pre(vec![], vec![div(vec![], vec![text("code")])]),
+            ],
+        );
+        let expected = r#"<div>
+    <p><span>hello</span><span> world</span></p>
+    <ul>
+        <li>item</li>
+    </ul>
+    <pre><div>code</div></pre>
+</div>"#;
+        let mut buffer = String::new();
+        view.render(&mut buffer).expect("must render");
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn test_render_preformatted_descendants_inline() {
+        let view: Node<()> = div(
+            vec![],
+            vec![pre(
+                vec![],
+                vec![div(
+                    vec![],
+                    vec![ul(vec![], vec![li(vec![], vec![text("code")])])],
+                )],
+            )],
+        );
+        let expected = r#"<div>
+    <pre><div><ul><li>code</li></ul></div></pre>
+</div>"#;
+        let mut buffer = String::new();
+        view.render(&mut buffer).expect("must render");
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn test_render_with_options() {
+        let view: Node<()> = div(
+            vec![class("container"), id("main")],
+            vec![
+                section(
+                    vec![class("gallery"), attr("title", "the pictures of the app")],
+                    vec![],
+                ),
+                hr(vec![], vec![]),
+            ],
+        );
+        let options = RenderOptions::new()
+            .indent("\t")
+            .max_width(40)
+            .self_closing_slash(false);
+        let expected = "<div class=\"container\" id=\"main\">\n\t<section\n\t\tclass=\"gallery\"\n\t\ttitle=\"the pictures of the app\"\n\t></section>\n\t<hr>\n</div>";
+        let mut buffer = String::new();
+        view.render_with_options(&mut buffer, &options)
+            .expect("must render");
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn test_render_boolean_attributes() {
+        let view: Node<()> = div(
+            vec![],
+            vec![
+                input(
+                    vec![
+                        attr("checked", false),
+                        attr("disabled", true),
+                        attr("readonly", "readonly"),
+                        attr("value", false),
+                    ],
+                    vec![],
+                ),
+                select(
+                    vec![attr("multiple", false)],
+                    vec![option(vec![attr("selected", true)], vec![])],
+                ),
+            ],
+        );
+        let expected = r#"<div><input disabled readonly value="false"/><select><option selected></option></select></div>"#;
+        assert_eq!(expected, view.render_to_string());
+    }
+
+    #[test]
+    fn test_render_sorted_attributes() {
+        let view1: Node<()> = div(
+            vec![id("main"), class("app"), attr("data-count", 1)],
+            vec![],
+        );
+        let view2: Node<()> = div(
+            vec![attr("data-count", 1), class("app"), id("main")],
+            vec![],
+        );
+        let options = RenderOptions::compressed().sort_attributes(true);
+        let mut buffer1 = String::new();
+        view1
+            .render_with_options(&mut buffer1, &options)
+            .expect("must render");
+        let mut buffer2 = String::new();
+        view2
+            .render_with_options(&mut buffer2, &options)
+            .expect("must render");
+        let expected = r#"<div class="app" data-count="1" id="main"></div>"#;
+        assert_eq!(expected, buffer1);
+        assert_eq!(expected, buffer2);
+    }
 }
@@ -0,0 +1,266 @@
+//! render a complete html document around the view of the app
+//!
+use crate::{
+    html::{
+        attributes::attr,
+        head,
+        link,
+        meta,
+        script,
+        tags::{
+            style,
+            title,
+        },
+        text,
+    },
+    Component,
+    Node,
+    Render,
+    RenderOptions,
+};
+use std::fmt;
+
+/// Marks the styles which are inlined into the head of the document, so the
+/// program which hydrates the document doesn't inject them again
+pub(crate) const DATA_SAURON_STYLE: &str = "data-sauron-style";
+
+/// A complete html5 document, with the view of the app in the body.
+///
+/// The styles of the app, which `Program` would inject at runtime, are
+/// inlined into the head of the document, followed by the stylesheets.
+/// `Program::hydrate` only injects the styles which are not inlined. The
+/// scripts are placed at the end of the body, after the view.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::{html::*, HtmlDocument, Node, Render};
+///
+/// let view: Node<()> = main(vec![], vec![text("hello")]);
+/// let document = HtmlDocument::new(view)
+///     .title("Hello")
+///     .meta("viewport", "width=device-width, initial-scale=1")
+///     .styles(vec!["main { display: flex; }".to_string()])
+///     .stylesheet("/static/style.css")
+///     .inline_script("import init from '/pkg/client.js'; init();");
+/// let html = document.render_to_string();
+/// assert!(html.starts_with("<!DOCTYPE html><html><head>"));
+/// ```
+pub struct HtmlDocument<MSG> {
+    view: Node<MSG>,
+    title: Option<String>,
+    metas: Vec<(String, String)>,
+    styles: Vec<String>,
+    stylesheets: Vec<String>,
+    scripts: Vec<Script>,
+}
+
+/// a script at the end of the body of the document
+enum Script {
+    /// the url of the script
+    Src(String),
+    /// the code of a module script
+    Inline(String),
+}
+
+impl<MSG> HtmlDocument<MSG> {
+    /// create a document with this view as the content of the body
+    pub fn new(view: Node<MSG>) -> Self {
+        HtmlDocument {
+            view,
+            title: None,
+            metas: vec![],
+            styles: vec![],
+            stylesheets: vec![],
+            scripts: vec![],
+        }
+    }
+
+    /// create a document from the view and the styles of the app
+    pub fn from_component<APP>(app: &APP) -> Self
+    where
+        APP: Component<MSG>,
+        MSG: 'static,
+    {
+        Self::new(app.view()).styles(app.style())
+    }
+
+    /// set the title of the document
+    pub fn title(mut self, title: impl ToString) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// add a `<meta>` with this name and content to the head
+    pub fn meta(mut self, name: impl ToString, content: impl ToString) -> Self {
+        self.metas.push((name.to_string(), content.to_string()));
+        self
+    }
+
+    /// add css styles which are inlined into the head
+    pub fn styles(mut self, styles: Vec<String>) -> Self {
+        self.styles.extend(styles);
+        self
+    }
+
+    /// add a link to the stylesheet at this url into the head
+    pub fn stylesheet(mut self, href: impl ToString) -> Self {
+        self.stylesheets.push(href.to_string());
+        self
+    }
+
+    /// add the script at this url to the end of the body
+    pub fn script(mut self, src: impl ToString) -> Self {
+        self.scripts.push(Script::Src(src.to_string()));
+        self
+    }
+
+    /// add a module script with this code to the end of the body, such as
+    /// the code which loads the wasm of the app
+    pub fn inline_script(mut self, code: impl ToString) -> Self {
+        self.scripts.push(Script::Inline(code.to_string()));
+        self
+    }
+
+    fn view_head(&self) -> Node<MSG> {
+        let mut children = vec![meta(vec![attr("charset", "utf-8")], vec![])];
+        if let Some(doc_title) = &self.title {
+            children.push(title(vec![], vec![text(doc_title)]));
+        }
+        children.extend(self.metas.iter().map(|(name, content)| {
+            meta(vec![attr("name", name), attr("content", content)], vec![])
+        }));
+        children.extend(
+            self.styles.iter().map(|css| {
+                style(vec![attr(DATA_SAURON_STYLE, "")], vec![text(css)])
+            }),
+        );
+        children.extend(self.stylesheets.iter().map(|href| {
+            link(vec![attr("rel", "stylesheet"), attr("href", href)], vec![])
+        }));
+        head(vec![], children)
+    }
+
+    fn view_scripts(&self) -> Vec<Node<MSG>> {
+        self.scripts
+            .iter()
+            .map(|doc_script| {
+                match doc_script {
+                    Script::Src(src) => script(vec![attr("src", src)], vec![]),
+                    Script::Inline(code) => {
+                        script(vec![attr("type", "module")], vec![text(code)])
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl<MSG> Render for HtmlDocument<MSG> {
+    fn render_with_indent(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+        node_idx: &mut Option<usize>,
+        options: &RenderOptions,
+    ) -> fmt::Result {
+        write!(buffer, "<!DOCTYPE html>")?;
+        options.new_line(buffer, indent)?;
+        write!(buffer, "<html>")?;
+        options.new_line(buffer, indent + 1)?;
+        self.view_head().render_with_indent(
+            buffer,
+            indent + 1,
+            node_idx,
+            options,
+        )?;
+        options.new_line(buffer, indent + 1)?;
+        write!(buffer, "<body>")?;
+        options.new_line(buffer, indent + 2)?;
+        self.view
+            .render_with_indent(buffer, indent + 2, node_idx, options)?;
+        for doc_script in self.view_scripts() {
+            options.new_line(buffer, indent + 2)?;
+            doc_script.render_with_indent(
+                buffer,
+                indent + 2,
+                node_idx,
+                options,
+            )?;
+        }
+        options.new_line(buffer, indent + 1)?;
+        write!(buffer, "</body>")?;
+        options.new_line(buffer, indent)?;
+        write!(buffer, "</html>")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        html::{
+            attributes::class,
+            div,
+        },
+        Cmd,
+    };
+
+    struct App;
+
+    impl Component<()> for App {
+        fn style(&self) -> Vec<String> {
+            vec![".app > div { display: flex; }".to_string()]
+        }
+
+        fn update(&mut self, _msg: ()) -> Cmd<Self, ()> {
+            Cmd::none()
+        }
+
+        fn view(&self) -> Node<()> {
+            div(vec![class("app")], vec![text("hello")])
+        }
+    }
+
+    #[test]
+    fn render_document_of_component() {
+        let document = HtmlDocument::from_component(&App)
+            .title("Tom & Jerry")
+            .meta("description", "\"quoted\"")
+            .stylesheet("/static/style.css")
+            .script("/static/polyfill.js")
+            .inline_script("import init from '/pkg/app.js'; init();");
+        let expected = concat!(
+            "<!DOCTYPE html><html><head>",
+            r#"<meta charset="utf-8"/>"#,
+            "<title>Tom &amp; Jerry</title>",
+            r#"<meta name="description" content="&quot;quoted&quot;"/>"#,
+            r#"<style data-sauron-style="">.app > div { display: flex; }</style>"#,
+            r#"<link rel="stylesheet" href="/static/style.css"/>"#,
+            "</head><body>",
+            r#"<div class="app">hello</div>"#,
+            r#"<script src="/static/polyfill.js"></script>"#,
+            r#"<script type="module">import init from '/pkg/app.js'; init();</script>"#,
+            "</body></html>",
+        );
+        assert_eq!(expected, document.render_to_string());
+    }
+
+    #[test]
+    fn render_indented_document() {
+        let view: Node<()> = div(vec![], vec![text("hello")]);
+        let mut buffer = String::new();
+        HtmlDocument::new(view)
+            .render(&mut buffer)
+            .expect("must render");
+        let expected = r#"<!DOCTYPE html>
+<html>
+    <head>
+        <meta charset="utf-8"/>
+    </head>
+    <body>
+        <div>hello</div>
+    </body>
+</html>"#;
+        assert_eq!(expected, buffer);
+    }
+}
@@ -0,0 +1,147 @@
+//! the options on how the html is formatted when it is rendered
+//!
+use std::fmt;
+
+/// Options on how the html is formatted by `Render::render_with_options`.
+///
+/// The default options pretty print the html, indenting the child elements
+/// with 4 spaces. The elements which contain phrasing content, such as text
+/// or a `span`, are kept on one line, so the pretty printed html is displayed
+/// the same as the compressed html.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::{html::*, Node, Render, RenderOptions};
+///
+/// let view: Node<()> = div(vec![], vec![hr(vec![], vec![])]);
+/// let options = RenderOptions::new().indent("  ").self_closing_slash(false);
+/// let mut buffer = String::new();
+/// view.render_with_options(&mut buffer, &options).expect("must render");
+/// assert_eq!(buffer, "<div>\n  <hr>\n</div>");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// the string for each level of indentation, the html is compressed into
+    /// one line if this is `None`
+    indent: Option<String>,
+    /// the width of a line where the attributes of the opening tag are
+    /// wrapped into their own lines
+    max_width: Option<usize>,
+    /// render the self closing elements as `<br/>` instead of `<br>`
+    self_closing_slash: bool,
+    /// render the attributes sorted by their names
+    sort_attributes: bool,
+}
+
+impl RenderOptions {
+    /// the options which pretty print the html with 4 spaces indentation
+    pub fn new() -> Self {
+        RenderOptions {
+            indent: Some("    ".to_string()),
+            max_width: None,
+            self_closing_slash: true,
+            sort_attributes: false,
+        }
+    }
+
+    /// the options which render the html with no new lines and indentation
+    pub fn compressed() -> Self {
+        RenderOptions {
+            indent: None,
+            max_width: None,
+            self_closing_slash: true,
+            sort_attributes: false,
+        }
+    }
+
+    /// indent each level of the child elements with this string
+    pub fn indent(mut self, indent: impl ToString) -> Self {
+        self.indent = Some(indent.to_string());
+        self
+    }
+
+    /// wrap each attribute of the opening tag into its own line when the
+    /// line of the opening tag is longer than `max_width`
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// render the self closing elements as `<br/>` if true, otherwise
+    /// as `<br>`
+    pub fn self_closing_slash(mut self, self_closing_slash: bool) -> Self {
+        self.self_closing_slash = self_closing_slash;
+        self
+    }
+
+    /// render the attributes of the elements sorted by their names, such
+    /// that the html is the same regardless of the order of the attributes
+    /// in the view
+    pub fn sort_attributes(mut self, sort_attributes: bool) -> Self {
+        self.sort_attributes = sort_attributes;
+        self
+    }
+
+    /// true if the html is rendered with no new lines and indentation
+    pub fn is_compressed(&self) -> bool {
+        self.indent.is_none()
+    }
+
+    /// the same options, except that the html is compressed, these are used
+    /// in the descendants of the elements which preserve their whitespace
+    pub(crate) fn preformatted(&self) -> Self {
+        RenderOptions {
+            indent: None,
+            ..self.clone()
+        }
+    }
+
+    /// start a new line at this level of indentation, unless the html is
+    /// compressed
+    pub(crate) fn new_line(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+    ) -> fmt::Result {
+        match &self.indent {
+            Some(indent_str) => {
+                write!(buffer, "\n{}", indent_str.repeat(indent))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// true if a line with this level of indentation and content is too long
+    pub(crate) fn is_too_long(
+        &self,
+        indent: usize,
+        content_width: usize,
+    ) -> bool {
+        match (&self.indent, self.max_width) {
+            (Some(indent_str), Some(max_width)) => {
+                indent_str.chars().count() * indent + content_width > max_width
+            }
+            _ => false,
+        }
+    }
+
+    /// true if the attributes are rendered sorted by their names
+    pub(crate) fn is_sort_attributes(&self) -> bool {
+        self.sort_attributes
+    }
+
+    /// the end of the opening tag of the self closing elements
+    pub(crate) fn self_closing_end(&self) -> &'static str {
+        if self.self_closing_slash {
+            "/>"
+        } else {
+            ">"
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
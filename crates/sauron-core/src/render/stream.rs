@@ -0,0 +1,298 @@
+//! render the node into an `io::Write` in chunks, so the html can be sent
+//! while the rest of the page is still being rendered
+//!
+use super::{
+    merge_attributes,
+    render_end_tag,
+    render_start_tag,
+    render_text,
+    RenderOptions,
+};
+use crate::{
+    Element,
+    Node,
+};
+use std::{
+    fmt,
+    io::{
+        self,
+        BufWriter,
+        Write,
+    },
+};
+
+/// selects the elements where the writer is flushed after they are rendered
+type FlushAfter<MSG> = Box<dyn Fn(&Element<MSG>) -> bool>;
+
+/// Renders a node into an `io::Write`, flushing the writer after each of the
+/// elements selected with `flush_after` is rendered.
+///
+/// The output is the same as `Render::render_compressed`, but the html is
+/// written to the writer as it is rendered instead of being collected into a
+/// `String`. The writes are buffered in between the flushes, so each flush
+/// sends one chunk of html to the writer.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::{html::*, Node, StreamRenderer};
+///
+/// let rows: Vec<Node<()>> = (0..1000)
+///     .map(|i| tr(vec![], vec![td(vec![], vec![text(i)])]))
+///     .collect();
+/// let view: Node<()> = table(vec![], rows);
+///
+/// let mut output = vec![];
+/// StreamRenderer::new()
+///     .flush_after(|element| *element.tag() == "tr")
+///     .render(&view, &mut output)
+///     .expect("must render");
+/// ```
+///
+/// To stream into an async byte sink, render in a blocking task into a
+/// `ChunkWriter` which sends each flushed chunk into a channel of the sink.
+pub struct StreamRenderer<MSG> {
+    /// returns true if the writer is flushed after the element is rendered
+    flush_after: FlushAfter<MSG>,
+    /// the compressed options which the elements are rendered with
+    options: RenderOptions,
+}
+
+impl<MSG> StreamRenderer<MSG> {
+    /// create a renderer which flushes the writer only when the whole node
+    /// is rendered
+    pub fn new() -> Self {
+        StreamRenderer {
+            flush_after: Box::new(|_| false),
+            options: RenderOptions::compressed(),
+        }
+    }
+
+    /// flush the writer after each element where `flush_after` returns true
+    /// is rendered, together with its descendants
+    pub fn flush_after<F>(mut self, flush_after: F) -> Self
+    where
+        F: Fn(&Element<MSG>) -> bool + 'static,
+    {
+        self.flush_after = Box::new(flush_after);
+        self
+    }
+
+    /// render the attributes of the elements sorted by their names
+    pub fn sort_attributes(mut self, sort_attributes: bool) -> Self {
+        self.options = self.options.sort_attributes(sort_attributes);
+        self
+    }
+
+    /// render the node into the writer, the writer is flushed at the end
+    pub fn render<W>(&self, node: &Node<MSG>, writer: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut writer = IoWriter {
+            writer: BufWriter::new(writer),
+            error: None,
+        };
+        self.render_node(None, node, &mut writer, &mut Some(0))?;
+        writer.writer.flush()
+    }
+
+    fn render_node<W>(
+        &self,
+        parent: Option<&Element<MSG>>,
+        node: &Node<MSG>,
+        writer: &mut IoWriter<W>,
+        node_idx: &mut Option<usize>,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        match node {
+            Node::Element(element) => {
+                let merged_attributes = merge_attributes(element);
+                let result = render_start_tag(
+                    element,
+                    &merged_attributes,
+                    writer,
+                    0,
+                    node_idx,
+                    &self.options,
+                );
+                writer.check(result)?;
+                for child in element.get_children() {
+                    if let Some(idx) = node_idx.as_mut() {
+                        *idx += 1;
+                    }
+                    self.render_node(Some(element), child, writer, node_idx)?;
+                }
+                let result =
+                    render_end_tag(element, &merged_attributes, writer);
+                writer.check(result)?;
+                if (self.flush_after)(element) {
+                    writer.writer.flush()?;
+                }
+                Ok(())
+            }
+            Node::Text(text) => {
+                let result = render_text(parent, &text.text, writer);
+                writer.check(result)
+            }
+        }
+    }
+}
+
+impl<MSG> Default for StreamRenderer<MSG> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// writes the formatted html into an `io::Write`, keeping the io error which
+/// the `fmt::Write` can not return
+struct IoWriter<W: Write> {
+    writer: BufWriter<W>,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> IoWriter<W> {
+    /// convert the result of the formatting into the io error of the writer
+    fn check(&mut self, result: fmt::Result) -> io::Result<()> {
+        result.map_err(|_| {
+            self.error
+                .take()
+                .unwrap_or_else(|| io::Error::other("formatter error"))
+        })
+    }
+}
+
+impl<W: Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// An `io::Write` which passes each flushed chunk of bytes to a function,
+/// such as the sender of a channel which is streamed into a response body.
+pub struct ChunkWriter<F>
+where
+    F: FnMut(Vec<u8>) -> io::Result<()>,
+{
+    chunk: Vec<u8>,
+    send: F,
+}
+
+impl<F> ChunkWriter<F>
+where
+    F: FnMut(Vec<u8>) -> io::Result<()>,
+{
+    /// create a writer which passes the chunks to `send`, an error
+    /// returned by `send` stops the rendering
+    pub fn new(send: F) -> Self {
+        ChunkWriter {
+            chunk: vec![],
+            send,
+        }
+    }
+}
+
+impl<F> Write for ChunkWriter<F>
+where
+    F: FnMut(Vec<u8>) -> io::Result<()>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.chunk.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() {
+            Ok(())
+        } else {
+            (self.send)(std::mem::take(&mut self.chunk))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        prelude::*,
+        Render,
+    };
+
+    fn view_table() -> Node<()> {
+        table(
+            vec![class("big")],
+            (0..3)
+                .map(|i| {
+                    tr(
+                        vec![key(i)],
+                        vec![
+                            td(vec![], vec![text(i)]),
+                            td(vec![], vec![input(vec![value(i)], vec![])]),
+                        ],
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn same_as_render_compressed() {
+        let view = view_table();
+        let mut output = vec![];
+        StreamRenderer::new()
+            .render(&view, &mut output)
+            .expect("must render");
+        assert_eq!(view.render_to_string(), String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn flush_a_chunk_for_each_row() {
+        let view = view_table();
+        let mut chunks = vec![];
+        StreamRenderer::new()
+            .flush_after(|element| *element.tag() == "tr")
+            .render(
+                &view,
+                ChunkWriter::new(|chunk| {
+                    chunks.push(String::from_utf8(chunk).unwrap());
+                    Ok(())
+                }),
+            )
+            .expect("must render");
+        assert_eq!(
+            chunks,
+            vec![
+                r#"<table class="big"><tr key="0"><td>0</td><td><input value="0"/></td></tr>"#,
+                r#"<tr key="1"><td>1</td><td><input value="1"/></td></tr>"#,
+                r#"<tr key="2"><td>2</td><td><input value="2"/></td></tr>"#,
+                "</table>",
+            ]
+        );
+        assert_eq!(view.render_to_string(), chunks.concat());
+    }
+
+    #[test]
+    fn stop_at_the_error_of_the_writer() {
+        let view = view_table();
+        let mut sent = 0;
+        let result = StreamRenderer::new()
+            .flush_after(|element| *element.tag() == "tr")
+            .render(
+                &view,
+                ChunkWriter::new(|_chunk| {
+                    sent += 1;
+                    Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+                }),
+            );
+        assert_eq!(
+            result.map_err(|e| e.kind()),
+            Err(io::ErrorKind::BrokenPipe)
+        );
+        assert_eq!(sent, 1);
+    }
+}
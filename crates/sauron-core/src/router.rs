@@ -0,0 +1,254 @@
+//! provides a route table which resolves a url into the route of the app.
+//!
+//! The same router is used in the client to resolve the location of the
+//! browser, and in the server to resolve the requested url, such that the
+//! server rendered view matches the view of the client.
+//!
+use crate::{
+    html::attributes::{
+        decode_uri_component,
+        Query,
+    },
+    Sub,
+};
+use std::{
+    rc::Rc,
+    str::FromStr,
+};
+
+/// A table of url patterns and the function which creates the route
+/// of the app from the matched url.
+///
+/// A pattern is made of segments separated by `/`, where:
+/// - `:name` matches any one segment and captures it as the param `name`
+/// - `*name` matches the rest of the path and captures it as the param
+///   `name`, this can only be the last segment
+/// - anything else matches the segment literally
+///
+/// The routes are matched in the order they are added.
+///
+/// Example:
+/// ```rust
+/// use sauron_core::Router;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Route {
+///     Home,
+///     User(u32),
+///     Search(String),
+/// }
+///
+/// let router = Router::new()
+///     .route("/", |_| Some(Route::Home))
+///     .route("/users/:id", |params| Some(Route::User(params.parse("id")?)))
+///     .route("/search", |params| {
+///         Some(Route::Search(params.query("q").unwrap_or("").to_string()))
+///     });
+///
+/// assert_eq!(router.resolve("/users/1"), Some(Route::User(1)));
+/// assert_eq!(router.resolve("/users/frodo"), None);
+/// assert_eq!(
+///     router.resolve("/search?q=the%20ring"),
+///     Some(Route::Search("the ring".to_string()))
+/// );
+/// ```
+pub struct Router<ROUTE> {
+    routes: Vec<RouteEntry<ROUTE>>,
+}
+
+/// creates the route from the params of the matched url
+type ToRoute<ROUTE> = Rc<dyn Fn(&RouteParams) -> Option<ROUTE>>;
+
+struct RouteEntry<ROUTE> {
+    pattern: Vec<Segment>,
+    to_route: ToRoute<ROUTE>,
+}
+
+#[derive(Clone)]
+enum Segment {
+    Literal(String),
+    Param(String),
+    Rest(String),
+}
+
+/// The params captured from the path of the url, and the params of
+/// the query string of the url
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RouteParams {
+    params: Vec<(String, String)>,
+    query: Query,
+}
+
+impl<ROUTE> Router<ROUTE>
+where
+    ROUTE: 'static,
+{
+    /// create a router with no routes
+    pub fn new() -> Self {
+        Router { routes: vec![] }
+    }
+
+    /// add a route with the url pattern, `to_route` creates the route from
+    /// the params of the matched url, if it returns None the next routes
+    /// are tried.
+    ///
+    /// Panics if `*` is not the last segment of the pattern
+    pub fn route<F>(mut self, pattern: &str, to_route: F) -> Self
+    where
+        F: Fn(&RouteParams) -> Option<ROUTE> + 'static,
+    {
+        let pattern: Vec<Segment> =
+            path_segments(pattern).map(Segment::from).collect();
+        let rest_pos = pattern
+            .iter()
+            .position(|segment| matches!(segment, Segment::Rest(_)));
+        if let Some(rest_pos) = rest_pos {
+            assert_eq!(rest_pos, pattern.len() - 1, "`*` must be the last");
+        }
+        self.routes.push(RouteEntry {
+            pattern,
+            to_route: Rc::new(to_route),
+        });
+        self
+    }
+
+    /// resolve the url into a route, the url is the path and the optional
+    /// query and hash, ie: `/users/1?tab=posts`.
+    /// Returns None if no route matched the url.
+    pub fn resolve(&self, url: &str) -> Option<ROUTE> {
+        let url = url.split('#').next().unwrap_or("");
+        let mut parts = url.splitn(2, '?');
+        let path = parts.next().unwrap_or("");
+        let query = Query::from_search(parts.next().unwrap_or(""));
+        let segments: Vec<String> =
+            path_segments(path).map(decode_uri_component).collect();
+        self.routes.iter().find_map(|entry| {
+            let params = RouteParams {
+                params: entry.match_segments(&segments)?,
+                query: query.clone(),
+            };
+            (entry.to_route)(&params)
+        })
+    }
+
+    /// Creates a subscription which emits the msg returned from `f` with the
+    /// resolved route whenever the url of the browser is changed, either by
+    /// the browser back and forward buttons, `Browser::push_url`,
+    /// `Browser::replace_url` or by clicking on a link within the app.
+    pub fn on_change<F, MSG>(&self, f: F) -> Sub<MSG>
+    where
+        F: Fn(Option<ROUTE>) -> MSG + 'static,
+        MSG: 'static,
+    {
+        let router = self.clone();
+        Sub::on_url_change(move |url| f(router.resolve(&url)))
+    }
+}
+
+impl<ROUTE> Clone for Router<ROUTE> {
+    fn clone(&self) -> Self {
+        Router {
+            routes: self.routes.iter().map(RouteEntry::clone).collect(),
+        }
+    }
+}
+
+impl<ROUTE> Default for Router<ROUTE>
+where
+    ROUTE: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ROUTE> RouteEntry<ROUTE> {
+    /// returns the captured params if the path segments matched the pattern
+    fn match_segments(
+        &self,
+        segments: &[String],
+    ) -> Option<Vec<(String, String)>> {
+        let mut params = vec![];
+        for (i, pattern) in self.pattern.iter().enumerate() {
+            match pattern {
+                Segment::Literal(literal) => {
+                    if segments.get(i)? != literal {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    params.push((name.to_string(), segments.get(i)?.clone()));
+                }
+                Segment::Rest(name) => {
+                    let rest = segments.get(i..).unwrap_or(&[]).join("/");
+                    params.push((name.to_string(), rest));
+                    return Some(params);
+                }
+            }
+        }
+        if segments.len() == self.pattern.len() {
+            Some(params)
+        } else {
+            None
+        }
+    }
+}
+
+impl<ROUTE> Clone for RouteEntry<ROUTE> {
+    fn clone(&self) -> Self {
+        RouteEntry {
+            pattern: self.pattern.clone(),
+            to_route: Rc::clone(&self.to_route),
+        }
+    }
+}
+
+impl From<&str> for Segment {
+    fn from(segment: &str) -> Self {
+        if let Some(name) = segment.strip_prefix(':') {
+            Segment::Param(name.to_string())
+        } else if let Some(name) = segment.strip_prefix('*') {
+            Segment::Rest(name.to_string())
+        } else {
+            Segment::Literal(decode_uri_component(segment))
+        }
+    }
+}
+
+impl RouteParams {
+    /// the value of the param with this name captured from the path
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// parse the value of the param with this name captured from the path.
+    /// Returns None if there is no such param or if it can not be parsed
+    pub fn parse<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name)?.parse().ok()
+    }
+
+    /// the first value of this name in the query string
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name)
+    }
+
+    /// parse the first value of this name in the query string.
+    /// Returns None if there is no such value or if it can not be parsed
+    pub fn parse_query<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.query.parse(name)
+    }
+
+    /// all the params of the query string
+    pub fn query_params(&self) -> &Query {
+        &self.query
+    }
+}
+
+/// the non-empty segments of the path,
+/// so `/users/1/` and `users/1` are the same path
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
@@ -0,0 +1,260 @@
+//! provides subscriptions to events which are outside of the view,
+//! such as window and document events, an interval timer or url changes.
+//!
+//! The subscriptions are re-evaluated by the Program after every update,
+//! and only the listeners that are no longer in the subscriptions are removed.
+//!
+use crate::{
+    cmd::MapDispatch,
+    Callback,
+    Dispatch,
+    Event,
+};
+use std::{
+    any::Any,
+    rc::Rc,
+};
+
+/// Sub is the set of subscriptions of a component.
+/// This is returned from the `subscriptions` function of the component.
+///
+/// Example:
+/// ```rust,ignore
+/// fn subscriptions(&self) -> Sub<Msg> {
+///     if self.is_running {
+///         Sub::every(1000, || Msg::Tick)
+///     } else {
+///         Sub::none()
+///     }
+/// }
+/// ```
+pub struct Sub<MSG> {
+    /// the subscriptions in this Sub
+    pub subscriptions: Vec<Subscription<MSG>>,
+}
+
+/// A subscription to an event outside of the view
+pub enum Subscription<MSG> {
+    /// listens to the event with this name in the window
+    Window(&'static str, Callback<MSG>),
+    /// listens to the event with this name in the document
+    Document(&'static str, Callback<MSG>),
+    /// triggered periodically every interval in milliseconds
+    Interval(i32, mt_dom::Callback<(), MSG>),
+    /// triggered with the new url whenever the url of the browser is changed
+    UrlChange(mt_dom::Callback<String, MSG>),
+    /// a subscription which attaches its own listener
+    Custom(CustomSubscription<MSG>),
+}
+
+/// A subscription which attaches its own listener, this is used by the
+/// services such as `Storage::on_change` whose listeners need more than
+/// an event name.
+pub struct CustomSubscription<MSG> {
+    key: String,
+    start: StartListener<MSG>,
+}
+
+/// attaches the listener, the returned value detaches it when dropped
+type StartListener<MSG> = Rc<dyn Fn(Rc<dyn Dispatch<MSG>>) -> Box<dyn Any>>;
+
+/// Identifies a subscription regardless of its callback.
+/// Subscriptions with the same key are matched when the old and new
+/// subscriptions are diffed, such that the listener is reused.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SubscriptionKey {
+    /// a window event with this name
+    Window(&'static str),
+    /// a document event with this name
+    Document(&'static str),
+    /// an interval with this milliseconds
+    Interval(i32),
+    /// the url changes of the browser
+    UrlChange,
+    /// a custom subscription with this key
+    Custom(String),
+}
+
+impl<MSG> Sub<MSG>
+where
+    MSG: 'static,
+{
+    /// No subscriptions
+    pub fn none() -> Self {
+        Sub {
+            subscriptions: vec![],
+        }
+    }
+
+    /// combine all the subscriptions into one
+    pub fn batch(subs: Vec<Self>) -> Self {
+        Sub {
+            subscriptions: subs
+                .into_iter()
+                .flat_map(|sub| sub.subscriptions)
+                .collect(),
+        }
+    }
+
+    /// listen to the event of the window, ie: "resize", "keydown"
+    pub fn on_window<F>(event_name: &'static str, f: F) -> Self
+    where
+        F: Fn(Event) -> MSG + 'static,
+    {
+        Self::from(Subscription::Window(event_name, Callback::from(f)))
+    }
+
+    /// listen to the event of the document, ie: "visibilitychange"
+    pub fn on_document<F>(event_name: &'static str, f: F) -> Self
+    where
+        F: Fn(Event) -> MSG + 'static,
+    {
+        Self::from(Subscription::Document(event_name, Callback::from(f)))
+    }
+
+    /// emit the msg returned from `f` every interval in milliseconds
+    pub fn every<F>(interval: i32, f: F) -> Self
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let cb = mt_dom::Callback::from(move |_: ()| f());
+        Self::from(Subscription::Interval(interval, cb))
+    }
+
+    /// emit the msg returned from `f` with the new url, which is the path,
+    /// query and hash, whenever the url of the browser is changed.
+    /// The clicks on the links within the app are also intercepted,
+    /// navigating without reloading the page.
+    /// Use `Router::on_change` to receive the resolved route instead.
+    pub fn on_url_change<F>(f: F) -> Self
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        Self::from(Subscription::UrlChange(mt_dom::Callback::from(f)))
+    }
+
+    /// map the msg of the subscriptions of a child component
+    /// into the msg of the parent component
+    pub fn map_msg<F, MSG2>(self, f: F) -> Sub<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + 'static,
+        MSG2: 'static,
+    {
+        let mapper: mt_dom::Callback<MSG, MSG2> = mt_dom::Callback::from(f);
+        Sub {
+            subscriptions: self
+                .subscriptions
+                .into_iter()
+                .map(|sub| sub.map_callback(mapper.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl<MSG> From<Subscription<MSG>> for Sub<MSG> {
+    fn from(subscription: Subscription<MSG>) -> Self {
+        Sub {
+            subscriptions: vec![subscription],
+        }
+    }
+}
+
+impl<MSG> Subscription<MSG>
+where
+    MSG: 'static,
+{
+    /// the key of this subscription
+    pub fn key(&self) -> SubscriptionKey {
+        match self {
+            Subscription::Window(event_name, _) => {
+                SubscriptionKey::Window(event_name)
+            }
+            Subscription::Document(event_name, _) => {
+                SubscriptionKey::Document(event_name)
+            }
+            Subscription::Interval(interval, _) => {
+                SubscriptionKey::Interval(*interval)
+            }
+            Subscription::UrlChange(_) => SubscriptionKey::UrlChange,
+            Subscription::Custom(custom) => {
+                SubscriptionKey::Custom(custom.key.to_string())
+            }
+        }
+    }
+
+    fn map_callback<MSG2>(
+        self,
+        mapper: mt_dom::Callback<MSG, MSG2>,
+    ) -> Subscription<MSG2>
+    where
+        MSG2: 'static,
+    {
+        match self {
+            Subscription::Window(event_name, cb) => {
+                Subscription::Window(event_name, cb.map_callback(mapper))
+            }
+            Subscription::Document(event_name, cb) => {
+                Subscription::Document(event_name, cb.map_callback(mapper))
+            }
+            Subscription::Interval(interval, cb) => {
+                Subscription::Interval(interval, cb.map_callback(mapper))
+            }
+            Subscription::UrlChange(cb) => {
+                Subscription::UrlChange(cb.map_callback(mapper))
+            }
+            Subscription::Custom(custom) => {
+                Subscription::Custom(custom.map_callback(mapper))
+            }
+        }
+    }
+}
+
+impl<MSG> CustomSubscription<MSG>
+where
+    MSG: 'static,
+{
+    /// `start` attaches the listener which dispatches the msgs to the
+    /// program, the value it returns is dropped when the subscription is
+    /// removed, which should then detach the listener.
+    /// When the subscriptions are diffed, the listener of the subscription
+    /// with the same key is kept and `start` is not called again.
+    pub fn new<F>(key: impl ToString, start: F) -> Self
+    where
+        F: Fn(Rc<dyn Dispatch<MSG>>) -> Box<dyn Any> + 'static,
+    {
+        CustomSubscription {
+            key: key.to_string(),
+            start: Rc::new(start),
+        }
+    }
+
+    /// the key which identifies this subscription
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// attach the listener, the listener is detached when the returned
+    /// value is dropped
+    pub fn start(&self, program: Rc<dyn Dispatch<MSG>>) -> Box<dyn Any> {
+        (self.start)(program)
+    }
+
+    fn map_callback<MSG2>(
+        self,
+        mapper: mt_dom::Callback<MSG, MSG2>,
+    ) -> CustomSubscription<MSG2>
+    where
+        MSG2: 'static,
+    {
+        let start = self.start;
+        CustomSubscription {
+            key: self.key,
+            start: Rc::new(move |program: Rc<dyn Dispatch<MSG2>>| {
+                start(Rc::new(MapDispatch {
+                    program,
+                    mapper: mapper.clone(),
+                }))
+            }),
+        }
+    }
+}
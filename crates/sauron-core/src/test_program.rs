@@ -0,0 +1,283 @@
+//! A headless program which drives a Component without a browser,
+//! so the app can be tested using plain `cargo test`.
+//!
+use crate::{
+    diff,
+    html::{
+        attributes::Special,
+        LazyScope,
+    },
+    Attribute,
+    Component,
+    Dispatch,
+    MsgQueue,
+    Node,
+    Patch,
+};
+use thiserror::Error;
+
+/// Selects a node in the view of the `TestProgram`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    /// select the first element which has this value in its `key` attribute
+    Key(String),
+    /// select the node by the index of the children starting from the root
+    /// node, an empty path is the root node itself
+    Path(Vec<usize>),
+}
+
+impl Selector {
+    /// select the node with this key
+    pub fn key(key: impl ToString) -> Self {
+        Selector::Key(key.to_string())
+    }
+
+    /// select the node with this path
+    pub fn path(path: &[usize]) -> Self {
+        Selector::Path(path.to_vec())
+    }
+}
+
+/// The errors in firing an event with `TestProgram::fire_event`
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum FireEventError {
+    /// there is no node matched by the selector
+    #[error("no node matched: {0:?}")]
+    NoNodeMatched(Selector),
+    /// an event listener of the node needs a browser event, such as the
+    /// `MouseEvent` of `on_click`
+    #[error("unable to fire {event_name} to {selector:?}: {message}")]
+    UnexpectedEvent {
+        /// the selector of the node
+        selector: Selector,
+        /// the name of the fired event
+        event_name: String,
+        /// the reason the event can not be passed to the listener
+        message: String,
+    },
+}
+
+/// TestProgram is the equivalent of `Program` which doesn't need a browser.
+///
+/// The dispatched msgs are queued and are only processed when `step` or `run`
+/// is called, so the tests can inspect the app, the view and the patches
+/// from every step of the way.
+///
+/// Example:
+/// ```rust,ignore
+/// let mut program = TestProgram::new(App::default());
+/// program.fire_event(&Selector::key("name"), "input", Some("Sauron"))?;
+/// program.run();
+/// assert_eq!(program.app().name, "Sauron");
+/// ```
+pub struct TestProgram<APP, MSG>
+where
+    MSG: 'static,
+{
+    app: APP,
+    queue: MsgQueue<MSG>,
+    current_view: Node<MSG>,
+    previous_view: Option<Node<MSG>>,
+    /// the scope of the lazy nodes in the view
+    lazy_scope: LazyScope,
+}
+
+impl<APP, MSG> TestProgram<APP, MSG>
+where
+    MSG: 'static,
+    APP: Component<MSG> + 'static,
+{
+    /// create a TestProgram with the initial view of the app.
+    /// The Cmd from the `init` of the app is emitted and the msgs it dispatched
+    /// are queued.
+    pub fn new(app: APP) -> Self {
+        let lazy_scope = LazyScope::new();
+        let current_view = lazy_scope.view(|| app.view());
+        let program = TestProgram {
+            app,
+            queue: MsgQueue::new(),
+            current_view,
+            previous_view: None,
+            lazy_scope,
+        };
+        program.app.init().emit(&program.queue);
+        program
+    }
+
+    /// returns a reference to the app
+    pub fn app(&self) -> &APP {
+        &self.app
+    }
+
+    /// returns the view of the app at the latest step
+    pub fn view(&self) -> &Node<MSG> {
+        &self.current_view
+    }
+
+    /// the number of msgs which are not yet processed
+    pub fn pending_msgs(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// process the next msg in the queue, the Cmd returned from the `update`
+    /// is then emitted and the app view is re-rendered, unless the Cmd
+    /// indicates that it should not update.
+    /// Returns false if there is no msg to process
+    pub fn step(&mut self) -> bool {
+        match self.queue.pop() {
+            Some(msg) => {
+                let cmd = self.app.update(msg);
+                let should_update = cmd.should_update;
+                cmd.emit(&self.queue);
+                if should_update {
+                    let app = &self.app;
+                    let new_view = self.lazy_scope.view(|| app.view());
+                    let old_view =
+                        std::mem::replace(&mut self.current_view, new_view);
+                    self.previous_view = Some(old_view);
+                } else {
+                    // the view is not re-rendered, so there are no patches
+                    self.previous_view = None;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// process all the msgs including the msgs dispatched while processing,
+    /// until there is no more msg left in the queue.
+    /// Returns the number of steps executed.
+    pub fn run(&mut self) -> usize {
+        let mut steps = 0;
+        while self.step() {
+            steps += 1;
+        }
+        steps
+    }
+
+    /// the patches to transform the view from the previous step into the
+    /// view of the latest step
+    pub fn patches(&self) -> Vec<Patch<'_, MSG>> {
+        match &self.previous_view {
+            Some(previous_view) => diff(previous_view, &self.current_view),
+            None => vec![],
+        }
+    }
+
+    /// find the node in the current view with the selector
+    pub fn find(&self, selector: &Selector) -> Option<&Node<MSG>> {
+        match selector {
+            Selector::Key(key) => find_by_key(&self.current_view, key),
+            Selector::Path(path) => find_by_path(&self.current_view, path),
+        }
+    }
+
+    /// fire the event with `event_name` such as "input" or "focus",
+    /// to the node selected, the msgs returned by the attached event listeners
+    /// of the node is then queued.
+    /// The value is passed as the value of the `InputEvent`.
+    ///
+    /// Returns an error if there is no node matched by the selector, or if
+    /// the event listeners need a browser event, such as the `MouseEvent` of
+    /// `on_click`, in which case no msg is queued.
+    /// The msg of such listeners can be queued with `dispatch` instead.
+    pub fn fire_event(
+        &self,
+        selector: &Selector,
+        event_name: &str,
+        value: Option<&str>,
+    ) -> Result<(), FireEventError> {
+        let node = self
+            .find(selector)
+            .ok_or_else(|| FireEventError::NoNodeMatched(selector.clone()))?;
+        let attributes = node.get_attributes().unwrap_or(&[]);
+        let mut msgs = vec![];
+        for att in attributes.iter().filter(|att| *att.name() == event_name) {
+            let att_msgs =
+                emit_listeners(att, event_name, value).map_err(|message| {
+                    FireEventError::UnexpectedEvent {
+                        selector: selector.clone(),
+                        event_name: event_name.to_string(),
+                        message,
+                    }
+                })?;
+            msgs.extend(att_msgs);
+        }
+        for msg in msgs {
+            self.queue.dispatch(msg);
+        }
+        Ok(())
+    }
+}
+
+impl<APP, MSG> Dispatch<MSG> for TestProgram<APP, MSG>
+where
+    MSG: 'static,
+{
+    fn dispatch(&self, msg: MSG) {
+        self.queue.dispatch(msg)
+    }
+}
+
+fn find_by_key<'a, MSG>(
+    node: &'a Node<MSG>,
+    key: &str,
+) -> Option<&'a Node<MSG>> {
+    if node.get_key().map(|v| v.to_string() == key).unwrap_or(false) {
+        return Some(node);
+    }
+    node.get_children()?
+        .iter()
+        .find_map(|child| find_by_key(child, key))
+}
+
+fn find_by_path<'a, MSG>(
+    node: &'a Node<MSG>,
+    path: &[usize],
+) -> Option<&'a Node<MSG>> {
+    match path.split_first() {
+        Some((idx, rest)) => {
+            find_by_path(node.get_children()?.get(*idx)?, rest)
+        }
+        None => Some(node),
+    }
+}
+
+/// call the callbacks of the event listener attribute with the event, the
+/// event is mapped into the type which the callback of a typed event
+/// listener expects
+#[cfg(feature = "with-dom")]
+fn emit_listeners<MSG>(
+    att: &Attribute<MSG>,
+    event_name: &str,
+    value: Option<&str>,
+) -> Result<Vec<MSG>, String>
+where
+    MSG: 'static,
+{
+    crate::dom::events::Listener::of_attribute(att)
+        .iter()
+        .map(|listener| {
+            let event = crate::dom::events::SyntheticEvent {
+                name: event_name.to_string(),
+                value: value.map(ToString::to_string),
+            };
+            listener.emit(event.into()).map_err(|error| error.to_string())
+        })
+        .collect()
+}
+
+/// There is no data in the Event when compiled without the `with-dom`
+/// feature, so any listener can be fired
+#[cfg(not(feature = "with-dom"))]
+fn emit_listeners<MSG>(
+    att: &Attribute<MSG>,
+    _event_name: &str,
+    _value: Option<&str>,
+) -> Result<Vec<MSG>, String>
+where
+    MSG: 'static,
+{
+    Ok(att.get_callback().into_iter().map(|cb| cb.emit(())).collect())
+}
@@ -0,0 +1,93 @@
+#![deny(warnings)]
+use sauron_core::{
+    Cmd,
+    Dispatch,
+};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum ChildMsg {
+    Tick,
+    Resize(i32, i32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Child(ChildMsg),
+}
+
+struct ChildApp;
+struct App;
+
+/// a dispatcher which just records the dispatched msg
+#[derive(Clone)]
+struct Recorder(Rc<RefCell<Vec<Msg>>>);
+
+impl Dispatch<Msg> for Recorder {
+    fn dispatch(&self, msg: Msg) {
+        self.0.borrow_mut().push(msg);
+    }
+}
+
+#[test]
+fn child_cmd_is_dispatched_in_parent() {
+    let child_cmd: Cmd<ChildApp, ChildMsg> = Cmd::batch(vec![
+        Cmd::new(|program| program.dispatch(ChildMsg::Tick)),
+        Cmd::new(|program| program.dispatch(ChildMsg::Resize(800, 600))),
+    ]);
+    let cmd: Cmd<App, Msg> = child_cmd.map_msg(Msg::Child);
+
+    let recorder = Recorder(Rc::new(RefCell::new(vec![])));
+    cmd.emit(&recorder);
+
+    assert_eq!(
+        *recorder.0.borrow(),
+        vec![
+            Msg::Child(ChildMsg::Tick),
+            Msg::Child(ChildMsg::Resize(800, 600))
+        ]
+    );
+}
+
+#[test]
+fn mapped_dispatcher_can_be_kept_for_later() {
+    let stored: Rc<RefCell<Option<Rc<dyn Dispatch<ChildMsg>>>>> =
+        Rc::new(RefCell::new(None));
+    let stored_clone = Rc::clone(&stored);
+    let child_cmd: Cmd<ChildApp, ChildMsg> = Cmd::new(move |program| {
+        *stored_clone.borrow_mut() = Some(program);
+    });
+    let cmd: Cmd<App, Msg> = child_cmd.map_msg(Msg::Child);
+
+    let recorder = Recorder(Rc::new(RefCell::new(vec![])));
+    cmd.emit(&recorder);
+    assert!(recorder.0.borrow().is_empty());
+
+    // such as when an event listener or http response triggers at a later time
+    stored
+        .borrow()
+        .as_ref()
+        .expect("must have the program")
+        .dispatch(ChildMsg::Tick);
+    assert_eq!(*recorder.0.borrow(), vec![Msg::Child(ChildMsg::Tick)]);
+}
+
+#[test]
+fn no_render_is_kept_when_batched_and_mapped() {
+    let cmd: Cmd<ChildApp, ChildMsg> = Cmd::no_render();
+    assert!(!cmd.should_update);
+    let cmd: Cmd<App, Msg> = cmd.map_msg(Msg::Child);
+    assert!(!cmd.should_update);
+
+    let cmd: Cmd<App, Msg> =
+        Cmd::batch(vec![Cmd::no_render(), Cmd::no_render()]);
+    assert!(!cmd.should_update);
+
+    // render if one of the batched Cmd needs it
+    let cmd: Cmd<App, Msg> = Cmd::batch(vec![Cmd::no_render(), Cmd::none()]);
+    assert!(cmd.should_update);
+    assert!(Cmd::<App, Msg>::batch(vec![]).should_update);
+}
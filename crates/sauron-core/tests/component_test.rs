@@ -0,0 +1,93 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        events::*,
+        *,
+    },
+    Cmd,
+    Component,
+    Dispatch,
+    MsgQueue,
+    Node,
+    Render,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Increment,
+    Reset,
+}
+
+struct Counter {
+    count: i32,
+}
+
+impl Component<Msg> for Counter {
+    fn init(&self) -> Cmd<Self, Msg> {
+        Cmd::new(|program| program.dispatch(Msg::Increment))
+    }
+
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => {
+                self.count += 1;
+                if self.count >= 3 {
+                    Cmd::new(|program| program.dispatch(Msg::Reset))
+                } else {
+                    Cmd::none()
+                }
+            }
+            Msg::Reset => {
+                self.count = 0;
+                Cmd::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            vec![class("counter")],
+            vec![
+                button(
+                    vec![class("increment"), on_click(|_| Msg::Increment)],
+                    vec![text("+")],
+                ),
+                text(self.count),
+            ],
+        )
+    }
+}
+
+/// process the queued msgs until there are no more msgs
+fn run(app: &mut Counter, queue: &MsgQueue<Msg>) {
+    while let Some(msg) = queue.pop() {
+        app.update(msg).emit(queue);
+    }
+}
+
+#[test]
+fn update_and_view_without_browser() {
+    let mut app = Counter { count: 0 };
+    let queue = MsgQueue::new();
+    app.init().emit(&queue);
+    assert_eq!(queue.len(), 1);
+    run(&mut app, &queue);
+    assert_eq!(app.count, 1);
+    assert!(queue.is_empty());
+
+    queue.dispatch(Msg::Increment);
+    run(&mut app, &queue);
+    assert_eq!(app.count, 2);
+    assert!(app.view().render_to_string().ends_with("</button>2</div>"));
+}
+
+#[test]
+fn cmd_from_update_is_queued() {
+    let mut app = Counter { count: 2 };
+    let queue = MsgQueue::new();
+    app.update(Msg::Increment).emit(&queue);
+    assert_eq!(app.count, 3);
+    assert_eq!(queue.take(), vec![Msg::Reset]);
+    assert!(queue.is_empty());
+}
@@ -48,6 +48,7 @@ fn nested_divs() {
         &mut None,
         &mut None,
     )
+    .expect("must create the node")
     .node
     .unchecked_into();
 
@@ -70,6 +71,7 @@ fn svg_element() {
         &mut None,
         &mut None,
     )
+    .expect("must create the node")
     .node
     .unchecked_into();
 
@@ -89,6 +91,7 @@ fn div_with_attributes() {
         &mut None,
         &mut None,
     )
+    .expect("must create the node")
     .node
     .unchecked_into();
 
@@ -123,7 +126,8 @@ fn click_event() {
     );
 
     let _dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program(), vdiv, &body);
+        DomUpdater::new_append_to_mount(&simple_program(), vdiv, &body)
+            .expect("must mount");
 
     let click_event = web_sys::MouseEvent::new("click").unwrap();
 
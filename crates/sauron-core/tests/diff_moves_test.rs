@@ -0,0 +1,157 @@
+#![deny(warnings)]
+use sauron_core::{
+    diff,
+    diff_with_moves,
+    html::{
+        attributes::*,
+        *,
+    },
+    mt_dom::patch::*,
+    DomPatch,
+    MoveNode,
+    MoveTarget,
+    Node,
+    Patch,
+    Text,
+};
+
+fn card(name: &'static str, content: &'static str) -> Node<()> {
+    li(vec![key(name)], vec![text(content)])
+}
+
+fn board(column1: Vec<Node<()>>, column2: Vec<Node<()>>) -> Node<()> {
+    main(
+        vec![],
+        vec![
+            ul(vec![key("column1")], column1),
+            ul(vec![key("column2")], column2),
+        ],
+    )
+}
+
+#[test]
+fn move_to_the_end_of_another_parent() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![card("c", "c")]);
+    let new = board(vec![card("b", "b")], vec![card("c", "c"), card("a", "a")]);
+
+    // 0 main, 1 ul, 2 li a, 3 text, 4 li b, 5 text, 6 ul, 7 li c, 8 text
+    assert_eq!(
+        diff_with_moves(&old, &new),
+        vec![MoveNode {
+            tag: Some(&"li"),
+            node_idx: 2,
+            target: MoveTarget::AppendTo(6),
+            patches: vec![],
+        }
+        .into()]
+    );
+}
+
+#[test]
+fn move_before_a_node_of_another_parent() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![card("c", "c")]);
+    let new = board(vec![card("b", "b")], vec![card("a", "a"), card("c", "c")]);
+
+    assert_eq!(
+        diff_with_moves(&old, &new),
+        vec![MoveNode {
+            tag: Some(&"li"),
+            node_idx: 2,
+            target: MoveTarget::InsertBefore(7),
+            patches: vec![],
+        }
+        .into()]
+    );
+}
+
+#[test]
+fn moved_node_is_patched() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![card("c", "c")]);
+    let new = board(vec![card("b", "b")], vec![card("c", "c"), card("a", "A")]);
+
+    // the patches of the moved node is indexed from the moved node
+    assert_eq!(
+        diff_with_moves(&old, &new),
+        vec![MoveNode {
+            tag: Some(&"li"),
+            node_idx: 2,
+            target: MoveTarget::AppendTo(6),
+            patches: vec![DomPatch::Patch(
+                ChangeText::new(1, &Text::new("a"), 1, &Text::new("A")).into()
+            )],
+        }
+        .into()]
+    );
+}
+
+#[test]
+fn appended_children_around_the_moved_node() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![]);
+    let new = board(
+        vec![card("b", "b")],
+        vec![card("x", "x"), card("a", "a"), card("y", "y")],
+    );
+    let x = card("x", "x");
+    let y = card("y", "y");
+
+    // the appended children has the node index of the new tree
+    // 0 main, 1 ul, 2 li b, 3 text, 4 ul, 5 li x, 6 text, 7 li a, 8 text, 9 li y
+    assert_eq!(
+        diff_with_moves(&old, &new),
+        vec![
+            DomPatch::Patch(
+                AppendChildren::new(&"ul", 6, vec![(5, &x)]).into()
+            ),
+            MoveNode {
+                tag: Some(&"li"),
+                node_idx: 2,
+                target: MoveTarget::AppendTo(6),
+                patches: vec![],
+            }
+            .into(),
+            DomPatch::Patch(
+                AppendChildren::new(&"ul", 6, vec![(9, &y)]).into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_keys_are_not_moved() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![card("a", "a")]);
+    let new = board(vec![card("b", "b")], vec![card("a", "a"), card("a", "a")]);
+
+    let patches: Vec<DomPatch<()>> =
+        diff(&old, &new).into_iter().map(DomPatch::from).collect();
+    assert_eq!(diff_with_moves(&old, &new), patches);
+}
+
+#[test]
+fn no_moves_is_the_same_as_diff() {
+    let old = board(vec![card("a", "a")], vec![card("c", "c")]);
+    let new = board(vec![card("a", "A")], vec![]);
+
+    let patches: Vec<DomPatch<()>> =
+        diff(&old, &new).into_iter().map(DomPatch::from).collect();
+    assert_eq!(diff_with_moves(&old, &new), patches);
+}
+
+#[test]
+fn keyed_node_of_a_replaced_parent_is_recreated() {
+    let old = board(vec![card("a", "a"), card("b", "b")], vec![card("c", "c")]);
+    // the first column is replaced, since its tag is changed
+    let new = main(
+        vec![],
+        vec![
+            ol(vec![key("column1")], vec![card("b", "b")]),
+            ul(vec![key("column2")], vec![card("c", "c"), card("a", "a")]),
+        ],
+    );
+
+    let patches: Vec<DomPatch<()>> =
+        diff(&old, &new).into_iter().map(DomPatch::from).collect();
+    assert!(patches
+        .iter()
+        .any(|patch| matches!(patch, DomPatch::Patch(Patch::ReplaceNode(_)))));
+    assert_eq!(diff_with_moves(&old, &new), patches);
+}
@@ -45,7 +45,8 @@ fn event_remove() {
             &"input",
             0,
             0,
-            vec![&on("input", |_| { () })],
+            // the typed listener is removed together with its event mapper
+            vec![&on_input(|_| ())],
         )
         .into()]
     );
@@ -0,0 +1,171 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::id,
+        div,
+        events::{
+            on,
+            on_input,
+        },
+        html_element,
+        text,
+    },
+    mt_dom::patch::ChangeText,
+    Cmd,
+    Component,
+    CreatedNode,
+    DomError,
+    DomUpdater,
+    Node,
+    Program,
+    Text,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+use test_fixtures::simple_program;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+mod test_fixtures;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn invalid_tag_is_an_error() {
+    let vdom: Node<()> = html_element("not a tag", vec![], vec![]);
+    let result = CreatedNode::create_dom_node(
+        &simple_program(),
+        &mut HashMap::new(),
+        &vdom,
+        &mut None,
+        &mut None,
+    );
+    assert!(matches!(
+        result,
+        Err(DomError::CreateElement { tag, .. }) if tag == "not a tag"
+    ));
+}
+
+#[wasm_bindgen_test]
+fn patch_to_a_missing_node_is_an_error() {
+    let simple_program = simple_program();
+    let vdom: Node<()> = div(vec![], vec![text("hello")]);
+    let mut dom_updater = DomUpdater::new_append_to_mount(
+        &simple_program,
+        vdom,
+        &sauron_core::body(),
+    )
+    .expect("must mount");
+
+    let result = dom_updater.patch_dom(
+        &simple_program,
+        vec![
+            ChangeText::new(99, &Text::new("hello"), 99, &Text::new("bye"))
+                .into(),
+        ],
+    );
+    assert_eq!(result, Err(DomError::NodeNotFound(99)));
+}
+
+#[derive(Default)]
+struct App {
+    inputs: Vec<String>,
+}
+
+impl Component<String> for App {
+    fn update(&mut self, msg: String) -> Cmd<Self, String> {
+        self.inputs.push(msg);
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<String> {
+        // an input event in a div is not mapped into an InputEvent
+        div(
+            vec![id("not-an-input"), on_input(|event| event.value)],
+            vec![],
+        )
+    }
+}
+
+#[wasm_bindgen_test]
+fn unexpected_event_target_is_reported_to_the_error_hook() {
+    let program = Program::mount_to_body(App::default());
+    let errors = Rc::new(RefCell::new(vec![]));
+    let errors_clone = Rc::clone(&errors);
+    program.on_error(move |error| errors_clone.borrow_mut().push(error));
+
+    let event = web_sys::InputEvent::new("input").expect("must create event");
+    sauron_core::document()
+        .get_element_by_id("not-an-input")
+        .expect("must have the div")
+        .dispatch_event(&event)
+        .expect("must dispatch");
+
+    assert_eq!(
+        *errors.borrow(),
+        vec![DomError::UnexpectedEvent {
+            event: "input".to_string(),
+            expected: "an input or textarea target",
+        }]
+    );
+    // the msg is not dispatched
+    assert!(program.app.borrow().inputs.is_empty());
+}
+
+#[derive(Default)]
+struct EditorApp {
+    inputs: Vec<String>,
+}
+
+impl Component<String> for EditorApp {
+    fn update(&mut self, msg: String) -> Cmd<Self, String> {
+        self.inputs.push(msg);
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<String> {
+        // only the typed listener maps the event into an InputEvent
+        div(
+            vec![
+                id("editor"),
+                on_input(|event| event.value),
+                on("input", |_event| "edited".to_string()),
+            ],
+            vec![],
+        )
+    }
+}
+
+/// resolves on the next animation frame, when the dispatched msgs are updated
+async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .request_animation_frame(&resolve)
+            .expect("must request animation frame");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+#[wasm_bindgen_test]
+async fn event_of_on_is_passed_as_is() {
+    let program = Program::mount_to_body(EditorApp::default());
+    let errors = Rc::new(RefCell::new(vec![]));
+    let errors_clone = Rc::clone(&errors);
+    program.on_error(move |error| errors_clone.borrow_mut().push(error));
+
+    let event = web_sys::InputEvent::new("input").expect("must create event");
+    sauron_core::document()
+        .get_element_by_id("editor")
+        .expect("must have the editor")
+        .dispatch_event(&event)
+        .expect("must dispatch");
+
+    // the typed listener reports the error, while the msg of `on` is
+    // dispatched
+    assert_eq!(errors.borrow().len(), 1);
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().inputs, vec!["edited".to_string()]);
+}
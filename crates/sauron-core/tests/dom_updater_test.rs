@@ -39,10 +39,13 @@ fn patches_dom() {
         &simple_program,
         vdom,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let new_vdom = div(vec![id("patched")], vec![]); //html! { <div id="patched"></div> };
-    dom_updater.update_dom(&simple_program, new_vdom);
+    dom_updater
+        .update_dom(&simple_program, new_vdom)
+        .expect("must update the dom");
 
     assert_eq!(document.query_selector("#patched").unwrap().is_some(), true);
 }
@@ -59,7 +62,8 @@ fn updates_active_closure_on_replace() {
     let simple_program = simple_program();
     let old = div(vec![], vec![]);
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
 
     let text = Rc::new(RefCell::new("Start Text".to_string()));
     let text_clone = Rc::clone(&text);
@@ -80,7 +84,9 @@ fn updates_active_closure_on_replace() {
     // New node replaces old node.
     // We are testing that we've stored this new node's closures even though `new` will be dropped
     // at the end of this block.
-    dom_updater.update_dom(&simple_program, replace_node);
+    dom_updater
+        .update_dom(&simple_program, replace_node)
+        .expect("must update the dom");
 
     let input_event = InputEvent::new("input").unwrap();
 
@@ -107,7 +113,8 @@ fn updates_active_closures_on_append() {
     let old = div(vec![], vec![]);
     let simple_program = simple_program();
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
 
     let text = Rc::new(RefCell::new("Start Text".to_string()));
     let text_clone = Rc::clone(&text);
@@ -134,7 +141,9 @@ fn updates_active_closures_on_append() {
         // New node gets appended into the DOM.
         // We are testing that we've stored this new node's closures even though `new` will be dropped
         // at the end of this block.
-        dom_updater.update_dom(&simple_program, append_node);
+        dom_updater
+            .update_dom(&simple_program, append_node)
+            .expect("must update the dom");
     }
 
     let input_event = InputEvent::new("input").unwrap();
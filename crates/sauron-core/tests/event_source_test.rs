@@ -0,0 +1,111 @@
+#![deny(warnings)]
+use sauron_core::{
+    sub::Subscription,
+    EventSource,
+    MsgQueue,
+    ServerMessage,
+    StreamState,
+};
+use serde::Deserialize;
+use std::rc::Rc;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Price {
+    cents: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Notice(String),
+    Price(Option<Price>),
+    State(StreamState),
+}
+
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        web_sys::window()
+            .expect("must have window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+            .expect("must set timeout");
+    });
+    JsFuture::from(promise).await.expect("must sleep");
+}
+
+/// a stream served from a data url, the browser reconnects to it after
+/// the `retry` delay once the stream ends
+fn stream_url(body: &str) -> String {
+    format!(
+        "data:text/event-stream,{}",
+        String::from(js_sys::encode_uri_component(body))
+    )
+}
+
+#[wasm_bindgen_test]
+fn decode_json_data() {
+    let message = ServerMessage {
+        event: "price".to_string(),
+        data: r#"{"cents": 1250}"#.to_string(),
+        last_event_id: "7".to_string(),
+    };
+    assert_eq!(message.json::<Price>().ok(), Some(Price { cents: 1250 }));
+    let invalid = ServerMessage {
+        data: "{".to_string(),
+        ..message
+    };
+    assert!(invalid.json::<Price>().is_err());
+}
+
+#[wasm_bindgen_test]
+async fn named_events_are_mapped_into_msgs() {
+    let url = stream_url(
+        "retry: 60000\n\
+         data: hello\n\n\
+         event: price\ndata: {\"cents\": 1250}\n\n\
+         event: ignored\ndata: {}\n\n",
+    );
+    let sub = EventSource::new(&url)
+        .on("message", |message| Msg::Notice(message.data))
+        .on_json("price", |price| Msg::Price(price.ok()))
+        .on_state(Msg::State)
+        .subscribe();
+    let queue = MsgQueue::new();
+    let stream = match &sub.subscriptions[0] {
+        Subscription::Custom(custom) => custom.start(Rc::new(queue.clone())),
+        _ => panic!("must be a custom subscription"),
+    };
+    sleep(200).await;
+
+    assert_eq!(
+        queue.take(),
+        vec![
+            Msg::State(StreamState::Open),
+            Msg::Notice("hello".to_string()),
+            Msg::Price(Some(Price { cents: 1250 })),
+            Msg::State(StreamState::Reconnecting),
+        ]
+    );
+
+    // the stream is closed when the subscription is removed
+    drop(stream);
+    sleep(100).await;
+    assert!(queue.is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn failed_stream_is_closed() {
+    let sub = EventSource::new("data:text/plain,not a stream")
+        .on("message", |message| Msg::Notice(message.data))
+        .on_state(Msg::State)
+        .subscribe();
+    let queue = MsgQueue::new();
+    let _stream = match &sub.subscriptions[0] {
+        Subscription::Custom(custom) => custom.start(Rc::new(queue.clone())),
+        _ => panic!("must be a custom subscription"),
+    };
+    sleep(200).await;
+    assert_eq!(queue.take(), vec![Msg::State(StreamState::Closed)]);
+}
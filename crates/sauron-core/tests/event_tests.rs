@@ -51,7 +51,8 @@ fn on_input_test() {
     let body = sauron_core::body();
     let simple_program = simple_program();
     let _dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, input, &body);
+        DomUpdater::new_append_to_mount(&simple_program, input, &body)
+            .expect("must mount");
 
     let input_element =
         sauron_core::document().get_element_by_id(&elem_id).unwrap();
@@ -99,9 +100,12 @@ fn added_event() {
     let body = sauron_core::body();
     let simple_program = simple_program();
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
     // update to new dom with no event attached
-    dom_updater.update_dom(&simple_program, new);
+    dom_updater
+        .update_dom(&simple_program, new)
+        .expect("must update the dom");
 
     let input_element =
         sauron_core::document().get_element_by_id(&elem_id).unwrap();
@@ -152,9 +156,12 @@ fn remove_event() {
     let body = sauron_core::body();
     let simple_program = simple_program();
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
     // update to new dom with no event attached
-    dom_updater.update_dom(&simple_program, new);
+    dom_updater
+        .update_dom(&simple_program, new)
+        .expect("must update the dom");
 
     let input_element =
         sauron_core::document().get_element_by_id(&elem_id).unwrap();
@@ -201,13 +208,16 @@ fn remove_event_from_truncated_children() {
         ],
     );
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
     assert_eq!(
         dom_updater.active_closure_len(),
         5,
         "There should be 5 events attached to the DomUpdater"
     );
-    dom_updater.update_dom(&simple_program, new);
+    dom_updater
+        .update_dom(&simple_program, new)
+        .expect("must update the dom");
 
     assert_eq!(
         dom_updater.active_closure_len(),
@@ -247,13 +257,16 @@ fn remove_event_from_truncated_children_some_with_no_events() {
         "Should be a Truncate patch"
     );
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
     assert_eq!(
         dom_updater.active_closure_len(),
         3,
         "There should be 3 events attached to the DomUpdater"
     );
-    dom_updater.update_dom(&simple_program, new);
+    dom_updater
+        .update_dom(&simple_program, new)
+        .expect("must update the dom");
 
     assert_eq!(
         dom_updater.active_closure_len(),
@@ -275,13 +288,16 @@ fn remove_event_from_replaced_node() {
         vec![ReplaceNode::new(Some(&"div"), 0, 0, &p(vec![], vec![])).into()],
     );
     let mut dom_updater =
-        DomUpdater::new_append_to_mount(&simple_program, old, &body);
+        DomUpdater::new_append_to_mount(&simple_program, old, &body)
+            .expect("must mount");
     assert_eq!(
         dom_updater.active_closure_len(),
         1,
         "There should be 1 event attached to the DomUpdater"
     );
-    dom_updater.update_dom(&simple_program, new);
+    dom_updater
+        .update_dom(&simple_program, new)
+        .expect("must update the dom");
 
     assert_eq!(
         dom_updater.active_closure_len(),
@@ -0,0 +1,144 @@
+#![deny(warnings)]
+use sauron_core::{
+    Http,
+    HttpError,
+    HttpRequest,
+    HttpResponse,
+    RequestHandle,
+};
+use serde::Deserialize;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn response(status: u16, body: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        status_text: String::new(),
+        headers: vec![(
+            "content-type".to_string(),
+            "application/json".to_string(),
+        )],
+        body: body.to_string(),
+    }
+}
+
+#[wasm_bindgen_test]
+fn decode_json_response() {
+    let user: Result<User, HttpError> =
+        response(200, r#"{"id": 1, "name": "Frodo"}"#).json();
+    assert_eq!(
+        user,
+        Ok(User {
+            id: 1,
+            name: "Frodo".to_string()
+        })
+    );
+}
+
+#[wasm_bindgen_test]
+fn error_status_is_not_decoded() {
+    let not_found = response(404, "");
+    let user: Result<User, HttpError> = not_found.clone().json();
+    assert_eq!(user, Err(HttpError::Status(not_found)));
+}
+
+#[wasm_bindgen_test]
+fn invalid_json_is_a_decode_error() {
+    let user: Result<User, HttpError> = response(200, "{").json();
+    assert!(matches!(user, Err(HttpError::Decode(_))));
+}
+
+#[wasm_bindgen_test]
+fn headers_are_case_insensitive() {
+    let response = response(200, "");
+    assert_eq!(response.header("Content-Type"), Some("application/json"));
+    assert_eq!(response.header("accept"), None);
+}
+
+#[wasm_bindgen_test]
+async fn fetch_data_url() {
+    let response = Http::get(r#"data:application/json,{"id":2,"name":"Sam"}"#)
+        .fetch()
+        .await
+        .expect("must fetch");
+    assert!(response.is_success());
+    assert_eq!(response.header("content-type"), Some("application/json"));
+    let user: User = response.json().expect("must decode");
+    assert_eq!(user.name, "Sam");
+}
+
+#[wasm_bindgen_test]
+async fn aborted_by_handle() {
+    let handle = RequestHandle::new();
+    let request = Http::get("data:text/plain,aborted").abort_handle(&handle);
+    let result = spawn_fetch(request);
+    // let the request start
+    yield_now().await;
+    assert!(handle.is_pending());
+
+    handle.abort();
+    sleep(10).await;
+    assert_eq!(*result.borrow(), Some(Err(HttpError::Aborted)));
+    assert!(!handle.is_pending());
+}
+
+#[wasm_bindgen_test]
+async fn latest_request_wins() {
+    let handle = RequestHandle::new();
+    let request = Http::get("data:text/plain,first").latest_wins(&handle);
+    let first = spawn_fetch(request);
+    yield_now().await;
+    assert!(handle.is_pending());
+
+    let second = Http::get("data:text/plain,second")
+        .latest_wins(&handle)
+        .fetch()
+        .await
+        .expect("must fetch");
+    assert_eq!(second.body, "second");
+    sleep(10).await;
+    assert_eq!(*first.borrow(), Some(Err(HttpError::Aborted)));
+}
+
+type FetchResult = Rc<RefCell<Option<Result<HttpResponse, HttpError>>>>;
+
+/// fetch the request in the background, the result is stored when done
+fn spawn_fetch(request: HttpRequest) -> FetchResult {
+    let result = Rc::new(RefCell::new(None));
+    let result_clone = Rc::clone(&result);
+    wasm_bindgen_futures::spawn_local(async move {
+        *result_clone.borrow_mut() = Some(request.fetch().await);
+    });
+    result
+}
+
+/// let the spawned futures run until they are waiting for the network
+async fn yield_now() {
+    let promise = js_sys::Promise::resolve(&JsValue::NULL);
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        web_sys::window()
+            .expect("must have window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve, ms,
+            )
+            .expect("must set timeout");
+    });
+    JsFuture::from(promise).await.expect("must sleep");
+}
@@ -0,0 +1,218 @@
+#![deny(warnings)]
+use sauron_core::{
+    dom::DomUpdater,
+    html::{
+        attributes::*,
+        events::*,
+        *,
+    },
+    Cmd,
+    Component,
+    HtmlDocument,
+    Node,
+    Program,
+    Render,
+};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+use test_fixtures::simple_program;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+mod test_fixtures;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// mount the server rendered html of the view into a container in the body
+/// and return the root element of the rendered view
+fn server_rendered(html: &str) -> web_sys::Element {
+    let container = sauron_core::document()
+        .create_element("div")
+        .expect("must create container");
+    container.set_inner_html(html);
+    sauron_core::body()
+        .append_child(&container)
+        .expect("must append container");
+    container
+        .first_element_child()
+        .expect("must have a rendered element")
+}
+
+#[wasm_bindgen_test]
+fn hydrate_reuses_server_rendered_nodes() {
+    let clicked = Rc::new(RefCell::new(false));
+    let clicked_clone = Rc::clone(&clicked);
+
+    let view: Node<()> = div(
+        vec![class("app")],
+        vec![
+            h1(vec![], vec![text("Hydrated")]),
+            button(
+                vec![
+                    id("hydrate-btn"),
+                    on_click(move |_| {
+                        *clicked_clone.borrow_mut() = true;
+                    }),
+                ],
+                vec![text("Click me")],
+            ),
+        ],
+    );
+
+    let root = server_rendered(&view.render_to_string());
+    let server_button = sauron_core::document()
+        .get_element_by_id("hydrate-btn")
+        .expect("must have the server rendered button");
+
+    let simple_program = simple_program();
+    let mut dom_updater = DomUpdater::new(view, &root);
+    dom_updater.hydrate(&simple_program).expect("must hydrate");
+
+    let button = sauron_core::document()
+        .get_element_by_id("hydrate-btn")
+        .expect("must still have the button");
+    assert!(button.is_same_node(Some(&server_button)));
+    assert_eq!(dom_updater.active_closure_len(), 1);
+
+    button.unchecked_ref::<web_sys::HtmlElement>().click();
+    assert!(*clicked.borrow());
+}
+
+#[wasm_bindgen_test]
+fn hydrate_repairs_mismatches() {
+    let server_view: Node<()> = div(
+        vec![class("app")],
+        vec![
+            p(vec![], vec![text("old text")]),
+            span(vec![], vec![text("not in the client")]),
+            blockquote(vec![], vec![]),
+        ],
+    );
+    let client_view: Node<()> = div(
+        vec![class("app")],
+        vec![
+            p(vec![], vec![text("new text")]),
+            ul(vec![], vec![li(vec![], vec![text("item")])]),
+        ],
+    );
+
+    let root = server_rendered(&server_view.render_to_string());
+
+    let simple_program = simple_program();
+    let expected = client_view.render_to_string();
+    let mut dom_updater = DomUpdater::new(client_view, &root);
+    dom_updater.hydrate(&simple_program).expect("must hydrate");
+
+    let root_element: web_sys::Element =
+        dom_updater.root_node().unchecked_into();
+    assert_eq!(expected, root_element.outer_html());
+}
+
+#[wasm_bindgen_test]
+fn hydrate_removes_the_attributes_which_are_not_in_the_view() {
+    let server_view: Node<()> = div(
+        vec![
+            class("app"),
+            attr("title", "server only"),
+            attr("data-sauron-vdom-id", "7"),
+        ],
+        vec![input(vec![value("x"), disabled(true)], vec![])],
+    );
+    let client_view: Node<()> =
+        div(vec![class("app")], vec![input(vec![value("x")], vec![])]);
+
+    let root = server_rendered(&server_view.render_to_string());
+
+    let simple_program = simple_program();
+    let mut dom_updater = DomUpdater::new(client_view, &root);
+    dom_updater.hydrate(&simple_program).expect("must hydrate");
+
+    let root_element: web_sys::Element =
+        dom_updater.root_node().unchecked_into();
+    assert!(!root_element.has_attribute("title"));
+    assert_eq!(root_element.get_attribute("class").as_deref(), Some("app"));
+    assert_eq!(
+        root_element.get_attribute("data-sauron-vdom-id").as_deref(),
+        Some("7")
+    );
+    let input = root_element
+        .first_element_child()
+        .expect("must have the input");
+    assert!(!input.has_attribute("disabled"));
+    assert_eq!(input.get_attribute("value").as_deref(), Some("x"));
+}
+
+#[wasm_bindgen_test]
+fn hydrate_indented_render() {
+    let view: Node<()> = main(
+        vec![],
+        vec![
+            div(vec![], vec![text("one")]),
+            div(vec![], vec![text("two")]),
+        ],
+    );
+
+    let mut buffer = String::new();
+    view.render(&mut buffer).expect("must render");
+    let root = server_rendered(&buffer);
+
+    let simple_program = simple_program();
+    let expected = view.render_to_string();
+    let mut dom_updater = DomUpdater::new(view, &root);
+    dom_updater.hydrate(&simple_program).expect("must hydrate");
+
+    // the whitespace in between elements are removed
+    let root_element: web_sys::Element =
+        dom_updater.root_node().unchecked_into();
+    assert_eq!(expected, root_element.outer_html());
+}
+
+struct StyledApp;
+
+impl Component<()> for StyledApp {
+    fn style(&self) -> Vec<String> {
+        vec![".styled-app { color: red; }".to_string()]
+    }
+
+    fn update(&mut self, _msg: ()) -> Cmd<Self, ()> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        div(vec![class("styled-app")], vec![text("styled")])
+    }
+}
+
+/// the number of style elements in the head with this css
+fn count_styles(css: &str) -> usize {
+    let styles = sauron_core::document()
+        .head()
+        .expect("must have a head")
+        .query_selector_all("style")
+        .expect("must select the styles");
+    (0..styles.length())
+        .filter_map(|i| styles.item(i))
+        .filter(|style| style.text_content().as_deref() == Some(css))
+        .count()
+}
+
+#[wasm_bindgen_test]
+fn hydrate_does_not_inject_the_inlined_styles_again() {
+    let document = HtmlDocument::from_component(&StyledApp);
+    let html = document.render_to_string();
+    let head_start = html.find("<head>").expect("must have a head") + 6;
+    let head_end = html.find("</head>").expect("must have a head");
+    sauron_core::document()
+        .head()
+        .expect("must have a head")
+        .insert_adjacent_html("beforeend", &html[head_start..head_end])
+        .expect("must insert the inlined styles");
+    let css = &StyledApp.style()[0];
+    assert_eq!(count_styles(css), 1);
+
+    let root = server_rendered(&StyledApp.view().render_to_string());
+    let _program = Program::hydrate(StyledApp, &root);
+    assert_eq!(count_styles(css), 1);
+}
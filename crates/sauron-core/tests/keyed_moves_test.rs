@@ -0,0 +1,96 @@
+#![deny(warnings)]
+use sauron_core::{
+    dom::DomUpdater,
+    html::{
+        attributes::*,
+        events::*,
+        *,
+    },
+    Node,
+};
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+use test_fixtures::simple_program;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+mod test_fixtures;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn board(
+    clicks: &Rc<Cell<u32>>,
+    column1: Vec<&'static str>,
+    column2: Vec<&'static str>,
+) -> Node<()> {
+    let card = |name: &'static str| {
+        let clicks = Rc::clone(clicks);
+        li(
+            vec![
+                key(name),
+                id(format!("card-{}", name)),
+                on_click(move |_| clicks.set(clicks.get() + 1)),
+            ],
+            vec![text(name)],
+        )
+    };
+    main(
+        vec![],
+        vec![
+            ul(
+                vec![key("column1"), id("column1")],
+                column1.into_iter().map(card).collect::<Vec<_>>(),
+            ),
+            ul(
+                vec![key("column2"), id("column2")],
+                column2.into_iter().map(card).collect::<Vec<_>>(),
+            ),
+        ],
+    )
+}
+
+fn click(element: &web_sys::Element) {
+    element.unchecked_ref::<web_sys::HtmlElement>().click();
+}
+
+#[wasm_bindgen_test]
+fn moved_node_keeps_its_identity_and_listeners() {
+    let clicks = Rc::new(Cell::new(0));
+    let simple_program = simple_program();
+    let mut dom_updater = DomUpdater::new_append_to_mount(
+        &simple_program,
+        board(&clicks, vec!["a", "b"], vec!["c"]),
+        &sauron_core::body(),
+    )
+    .expect("must mount");
+
+    let document = sauron_core::document();
+    let card_a = document.get_element_by_id("card-a").expect("must have a");
+    click(&card_a);
+    assert_eq!(clicks.get(), 1);
+
+    dom_updater
+        .update_dom(&simple_program, board(&clicks, vec!["b"], vec!["c", "a"]))
+        .expect("must update the dom");
+
+    let moved = document.get_element_by_id("card-a").expect("must have a");
+    assert!(moved.is_same_node(Some(&card_a)));
+    assert_eq!(
+        moved.parent_element().expect("must have a parent").id(),
+        "column2"
+    );
+    assert_eq!(
+        document
+            .get_element_by_id("column2")
+            .expect("must have column2")
+            .text_content(),
+        Some("ca".to_string())
+    );
+
+    // the event listener is moved together with the node
+    click(&moved);
+    assert_eq!(clicks.get(), 2);
+    assert_eq!(dom_updater.active_closures.len(), 3);
+}
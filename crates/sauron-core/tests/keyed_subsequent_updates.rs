@@ -174,7 +174,8 @@ fn subsequent_updates() {
         &simple_program,
         old.clone(),
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".editor")
@@ -207,7 +208,9 @@ fn subsequent_updates() {
 
     assert_eq!(expected, container.outer_html());
 
-    dom_updater.update_dom(&simple_program, update1.clone());
+    dom_updater
+        .update_dom(&simple_program, update1.clone())
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".editor")
@@ -325,7 +328,9 @@ fn subsequent_updates() {
         ]
     );
 
-    dom_updater.update_dom(&simple_program, update2.clone());
+    dom_updater
+        .update_dom(&simple_program, update2.clone())
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".editor")
@@ -453,7 +458,9 @@ fn subsequent_updates() {
         ]
     );
 
-    dom_updater.update_dom(&simple_program, update3.clone());
+    dom_updater
+        .update_dom(&simple_program, update3.clone())
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".editor")
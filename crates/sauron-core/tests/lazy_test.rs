@@ -0,0 +1,155 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::class,
+        *,
+    },
+    mt_dom::patch::*,
+    Cmd,
+    Component,
+    Dispatch,
+    Node,
+    Render,
+    TestProgram,
+    Text,
+};
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Select(usize),
+    Edit(usize, String),
+    Remove,
+    Restore,
+}
+
+struct App {
+    rows: Vec<String>,
+    removed: Option<String>,
+    selected: usize,
+    /// the number of times a row view is built
+    builds: Rc<Cell<usize>>,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            rows: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            removed: None,
+            selected: 0,
+            builds: Rc::new(Cell::new(0)),
+        }
+    }
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Select(selected) => self.selected = selected,
+            Msg::Edit(row, value) => self.rows[row] = value,
+            Msg::Remove => self.removed = self.rows.pop(),
+            Msg::Restore => self.rows.extend(self.removed.take()),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            vec![],
+            vec![
+                text(self.selected),
+                table(
+                    vec![],
+                    self.rows
+                        .iter()
+                        .map(|row| {
+                            lazy(row, || {
+                                self.builds.set(self.builds.get() + 1);
+                                tr(vec![], vec![td(vec![], vec![text(row)])])
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ],
+        )
+    }
+}
+
+fn dispatch(program: &mut TestProgram<App, Msg>, msg: Msg) {
+    program.dispatch(msg);
+    assert!(program.step());
+}
+
+#[test]
+fn unchanged_rows_are_not_rebuilt() {
+    let mut program = TestProgram::new(App::new());
+    assert_eq!(program.app().builds.get(), 3);
+
+    dispatch(&mut program, Msg::Select(2));
+    assert_eq!(program.app().builds.get(), 3);
+    assert_eq!(
+        program.patches(),
+        vec![ChangeText::new(1, &Text::new("0"), 1, &Text::new("2")).into()]
+    );
+}
+
+#[test]
+fn changed_row_is_rebuilt_and_diffed() {
+    let mut program = TestProgram::new(App::new());
+
+    dispatch(&mut program, Msg::Edit(1, "B".to_string()));
+    assert_eq!(program.app().builds.get(), 4);
+    // 0 div, 1 text, 2 table, 3 tr, 4 td, 5 text, 6 tr, 7 td, 8 text
+    let patches = program.patches();
+    assert!(patches.contains(
+        &ChangeText::new(8, &Text::new("b"), 8, &Text::new("B")).into()
+    ));
+    assert!(patches.iter().all(|patch| patch.node_idx() >= 6));
+}
+
+#[test]
+fn unused_rows_are_dropped_from_the_cache() {
+    let mut program = TestProgram::new(App::new());
+
+    dispatch(&mut program, Msg::Remove);
+    assert_eq!(program.app().builds.get(), 3);
+
+    dispatch(&mut program, Msg::Restore);
+    assert_eq!(program.app().builds.get(), 4);
+}
+
+#[test]
+fn rows_are_dropped_from_the_cache_with_the_program() {
+    let program = TestProgram::new(App::new());
+    let builds = Rc::clone(&program.app().builds);
+    drop(program);
+
+    let program = TestProgram::new(App {
+        builds,
+        ..App::new()
+    });
+    assert_eq!(program.app().builds.get(), 6);
+}
+
+#[test]
+fn memo_is_not_rendered() {
+    let row: Node<Msg> = lazy("a", || {
+        tr(vec![class("row")], vec![td(vec![], vec![text("a")])])
+    });
+    assert_eq!(row.render_to_string(), r#"<tr class="row"><td>a</td></tr>"#);
+}
+
+#[test]
+fn views_outside_of_a_program_are_not_cached() {
+    let builds = Cell::new(0);
+    for _ in 0..2 {
+        let _row: Node<Msg> = lazy("a", || {
+            builds.set(builds.get() + 1);
+            tr(vec![], vec![td(vec![], vec![text("a")])])
+        });
+    }
+    assert_eq!(builds.get(), 2);
+}
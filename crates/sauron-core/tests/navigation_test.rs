@@ -0,0 +1,113 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        *,
+    },
+    Browser,
+    Cmd,
+    Component,
+    Dispatch,
+    Node,
+    Program,
+    Router,
+    Sub,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Route {
+    Home,
+    User(u32),
+}
+
+enum Msg {
+    Navigate(String),
+    UrlChanged(Option<Route>),
+}
+
+struct App {
+    router: Router<Route>,
+    route: Option<Route>,
+}
+
+impl App {
+    fn new() -> Self {
+        let router = Router::new()
+            .route("/", |_| Some(Route::Home))
+            .route("/users/:id", |params| {
+                Some(Route::User(params.parse("id")?))
+            });
+        App {
+            route: router.resolve(&Browser::get_url()),
+            router,
+        }
+    }
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Navigate(url) => Browser::push_url(&url),
+            Msg::UrlChanged(route) => {
+                self.route = route;
+                Cmd::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        a(
+            vec![id("user-2"), href("/users/2")],
+            vec![text(format!("{:?}", self.route))],
+        )
+    }
+
+    fn subscriptions(&self) -> Sub<Msg> {
+        self.router.on_change(Msg::UrlChanged)
+    }
+}
+
+/// resolves on the next animation frame
+async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .request_animation_frame(&resolve)
+            .expect("must request animation frame");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+#[wasm_bindgen_test]
+async fn push_click_and_go_back() {
+    let program = Program::mount_to_body(App::new());
+
+    program.dispatch(Msg::Navigate("/users/1".to_string()));
+    next_animation_frame().await;
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().route, Some(Route::User(1)));
+    assert_eq!(Browser::get_url(), "/users/1");
+
+    let link: web_sys::HtmlElement = sauron_core::document()
+        .get_element_by_id("user-2")
+        .expect("must have the link")
+        .unchecked_into();
+    link.click();
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().route, Some(Route::User(2)));
+    assert_eq!(Browser::get_url(), "/users/2");
+
+    sauron_core::history().back().expect("must go back");
+    // popstate is triggered asynchronously after going back
+    let mut frames = 0;
+    while Browser::get_url() != "/users/1" && frames < 60 {
+        next_animation_frame().await;
+        frames += 1;
+    }
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().route, Some(Route::User(1)));
+}
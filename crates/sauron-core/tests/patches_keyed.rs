@@ -42,7 +42,8 @@ fn node_mounted_properly() {
         &simple_program,
         old,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".container1")
@@ -97,7 +98,8 @@ fn node_patched_properly() {
         &simple_program,
         old,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".container2")
@@ -113,7 +115,9 @@ fn node_patched_properly() {
 
     assert_eq!(expected, container.outer_html());
 
-    dom_updater.update_dom(&simple_program, update1);
+    dom_updater
+        .update_dom(&simple_program, update1)
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".container2")
@@ -172,7 +176,8 @@ fn node_patched_properly_remove_from_start() {
         &simple_program,
         old,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".test3")
@@ -189,7 +194,9 @@ fn node_patched_properly_remove_from_start() {
 
     assert_eq!(expected, container.outer_html());
 
-    dom_updater.update_dom(&simple_program, update1);
+    dom_updater
+        .update_dom(&simple_program, update1)
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".test3")
@@ -260,7 +267,8 @@ fn node_patched_properly_text_changed() {
         &simple_program,
         old,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".test4")
@@ -277,7 +285,9 @@ fn node_patched_properly_text_changed() {
 
     assert_eq!(expected, container.outer_html());
 
-    dom_updater.update_dom(&simple_program, update1);
+    dom_updater
+        .update_dom(&simple_program, update1)
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".test4")
@@ -359,7 +369,8 @@ fn mixed_keyed_and_non_keyed_elements() {
         &simple_program,
         old,
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
     let container = document
         .query_selector(".test5")
@@ -377,7 +388,9 @@ fn mixed_keyed_and_non_keyed_elements() {
 
     assert_eq!(expected, container.outer_html());
 
-    dom_updater.update_dom(&simple_program, update1);
+    dom_updater
+        .update_dom(&simple_program, update1)
+        .expect("must update the dom");
 
     let container = document
         .query_selector(".test5")
@@ -0,0 +1,72 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::*,
+    Cmd,
+    Component,
+    Dispatch,
+    Node,
+    Program,
+};
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Tick,
+}
+
+struct App {
+    ticks: u32,
+    views: Rc<Cell<u32>>,
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Tick => self.ticks += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        self.views.set(self.views.get() + 1);
+        div(vec![], vec![text(self.ticks)])
+    }
+}
+
+/// resolves on the next animation frame
+async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .request_animation_frame(&resolve)
+            .expect("must request animation frame");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+#[wasm_bindgen_test]
+async fn burst_of_msgs_are_rendered_once() {
+    let views = Rc::new(Cell::new(0));
+    let app = App {
+        ticks: 0,
+        views: Rc::clone(&views),
+    };
+    let program = Program::mount_to_body(app);
+    assert_eq!(views.get(), 1);
+
+    for _ in 0..5 {
+        program.dispatch(Msg::Tick);
+    }
+    assert_eq!(program.app.borrow().ticks, 0);
+
+    next_animation_frame().await;
+
+    assert_eq!(program.app.borrow().ticks, 5);
+    assert_eq!(program.last_merged_updates(), 5);
+    assert_eq!(views.get(), 2);
+}
@@ -0,0 +1,137 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        *,
+    },
+    sub::{
+        Subscription,
+        SubscriptionKey,
+    },
+    Node,
+    Render,
+    Router,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Route {
+    Home,
+    User(u32),
+    UserPosts(u32, u32),
+    Search(String, u32),
+    File(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    UrlChanged(Option<Route>),
+}
+
+fn router() -> Router<Route> {
+    Router::new()
+        .route("/", |_| Some(Route::Home))
+        .route("/users/:id", |params| {
+            Some(Route::User(params.parse("id")?))
+        })
+        .route("/users/:id/posts", |params| {
+            let page = params.parse_query("page").unwrap_or(1);
+            Some(Route::UserPosts(params.parse("id")?, page))
+        })
+        .route("/search", |params| {
+            let query = params.query("q").unwrap_or("").to_string();
+            Some(Route::Search(query, params.parse_query("page")?))
+        })
+        .route("/search", |params| {
+            Some(Route::Search(params.query("q")?.to_string(), 1))
+        })
+        .route("/files/*path", |params| {
+            Some(Route::File(params.get("path")?.to_string()))
+        })
+}
+
+#[test]
+fn resolve_paths() {
+    let router = router();
+    assert_eq!(router.resolve("/"), Some(Route::Home));
+    assert_eq!(router.resolve(""), Some(Route::Home));
+    assert_eq!(router.resolve("/users/7"), Some(Route::User(7)));
+    assert_eq!(router.resolve("/users/7/"), Some(Route::User(7)));
+    assert_eq!(router.resolve("/users/7#bio"), Some(Route::User(7)));
+    assert_eq!(
+        router.resolve("/users/7/posts"),
+        Some(Route::UserPosts(7, 1))
+    );
+    assert_eq!(router.resolve("/users"), None);
+    assert_eq!(router.resolve("/users/7/comments"), None);
+}
+
+#[test]
+fn params_which_can_not_be_parsed_are_not_matched() {
+    assert_eq!(router().resolve("/users/frodo"), None);
+}
+
+#[test]
+fn query_and_fallback_routes() {
+    let router = router();
+    assert_eq!(
+        router.resolve("/users/7/posts?page=3"),
+        Some(Route::UserPosts(7, 3))
+    );
+    assert_eq!(
+        router.resolve("/search?q=the+one%20ring&page=2"),
+        Some(Route::Search("the one ring".to_string(), 2))
+    );
+    // the first search route needs a page, so the next route is used
+    assert_eq!(
+        router.resolve("/search?q=mordor"),
+        Some(Route::Search("mordor".to_string(), 1))
+    );
+    assert_eq!(router.resolve("/search"), None);
+}
+
+#[test]
+fn rest_of_the_path_and_decoding() {
+    let router = router();
+    assert_eq!(
+        router.resolve("/files/maps/middle%20earth.png"),
+        Some(Route::File("maps/middle earth.png".to_string()))
+    );
+    assert_eq!(
+        router.resolve("/files/100%"),
+        Some(Route::File("100%".to_string()))
+    );
+}
+
+#[test]
+fn url_changes_are_resolved_into_routes() {
+    let sub = router().on_change(Msg::UrlChanged);
+    assert_eq!(sub.subscriptions.len(), 1);
+    assert_eq!(sub.subscriptions[0].key(), SubscriptionKey::UrlChange);
+    match &sub.subscriptions[0] {
+        Subscription::UrlChange(cb) => {
+            assert_eq!(
+                cb.emit("/users/3?tab=posts".to_string()),
+                Msg::UrlChanged(Some(Route::User(3)))
+            );
+            assert_eq!(cb.emit("/unknown".to_string()), Msg::UrlChanged(None));
+        }
+        _ => panic!("must be a url change subscription"),
+    }
+}
+
+#[test]
+fn server_renders_the_resolved_route() {
+    fn view(route: &Route) -> Node<Msg> {
+        match route {
+            Route::User(id) => {
+                a(vec![href(format!("/users/{}/posts", id))], vec![text(id)])
+            }
+            _ => text("other"),
+        }
+    }
+    let route = router().resolve("/users/42").expect("must resolve");
+    assert_eq!(
+        view(&route).render_to_string(),
+        r#"<a href="/users/42/posts">42</a>"#
+    );
+}
@@ -0,0 +1,123 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::text,
+    sub::Subscription,
+    Cmd,
+    Component,
+    MsgQueue,
+    Node,
+    Storage,
+    StorageError,
+};
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+use web_sys::{
+    StorageEvent,
+    StorageEventInit,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Loaded(Result<Option<Vec<String>>, StorageError>),
+    Failed(StorageError),
+}
+
+struct App;
+
+impl Component<Msg> for App {
+    fn update(&mut self, _msg: Msg) -> Cmd<Self, Msg> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        text("")
+    }
+}
+
+fn local_storage() -> web_sys::Storage {
+    sauron_core::window()
+        .local_storage()
+        .expect("must have local storage")
+        .expect("must have local storage")
+}
+
+#[wasm_bindgen_test]
+fn values_are_stored_with_namespaced_keys() {
+    let storage = Storage::local("storage-test");
+    let todos = vec!["write tests".to_string()];
+    storage.set("todos", &todos).expect("must set");
+
+    assert_eq!(
+        local_storage().get_item("storage-test::todos"),
+        Ok(Some(r#"["write tests"]"#.to_string()))
+    );
+    assert_eq!(storage.get("todos"), Ok(Some(todos)));
+
+    storage.remove("todos").expect("must remove");
+    assert_eq!(storage.get::<Vec<String>>("todos"), Ok(None));
+}
+
+#[wasm_bindgen_test]
+fn load_and_save_cmds() {
+    let storage = Storage::local("storage-cmd-test");
+    let queue = MsgQueue::new();
+
+    let save: Cmd<App, Msg> =
+        storage.save("todos", &vec!["a", "b"], Msg::Failed);
+    save.emit(&queue);
+    assert!(queue.is_empty());
+
+    let load: Cmd<App, Msg> = storage.load("todos", Msg::Loaded);
+    load.emit(&queue);
+    assert_eq!(
+        queue.take(),
+        vec![Msg::Loaded(Ok(Some(vec!["a".to_string(), "b".to_string()])))]
+    );
+
+    local_storage()
+        .set_item("storage-cmd-test::todos", "not json")
+        .expect("must set item");
+    let load: Cmd<App, Msg> = storage.load("todos", Msg::Loaded);
+    load.emit(&queue);
+    assert!(matches!(
+        queue.take().as_slice(),
+        [Msg::Loaded(Err(StorageError::Deserialize(_)))]
+    ));
+}
+
+#[wasm_bindgen_test]
+fn changes_from_other_tabs_are_dispatched() {
+    let storage = Storage::local("storage-sync-test");
+    let sub = storage.on_change("todos", Msg::Loaded);
+    let queue = MsgQueue::new();
+    let listener = match &sub.subscriptions[0] {
+        Subscription::Custom(custom) => custom.start(Rc::new(queue.clone())),
+        _ => panic!("must be a custom subscription"),
+    };
+
+    let dispatch_storage_event = |key: &str, new_value: &str| {
+        let init = StorageEventInit::new();
+        init.set_key(Some(key));
+        init.set_new_value(Some(new_value));
+        init.set_storage_area(Some(&local_storage()));
+        let event = StorageEvent::new_with_event_init_dict("storage", &init)
+            .expect("must create storage event");
+        sauron_core::window()
+            .dispatch_event(&event)
+            .expect("must dispatch");
+    };
+
+    dispatch_storage_event("storage-sync-test::todos", r#"["synced"]"#);
+    dispatch_storage_event("other-app::todos", r#"["ignored"]"#);
+    assert_eq!(
+        queue.take(),
+        vec![Msg::Loaded(Ok(Some(vec!["synced".to_string()])))]
+    );
+
+    // the listener is removed when dropped
+    drop(listener);
+    dispatch_storage_event("storage-sync-test::todos", r#"["removed"]"#);
+    assert!(queue.is_empty());
+}
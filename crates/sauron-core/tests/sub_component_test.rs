@@ -0,0 +1,144 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        *,
+    },
+    Cmd,
+    Component,
+    Dispatch,
+    Node,
+    Program,
+    SubComponent,
+};
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum CounterMsg {
+    Increment,
+    Reset,
+}
+
+struct Counter {
+    count: u32,
+    views: Rc<Cell<u32>>,
+}
+
+impl Component<CounterMsg> for Counter {
+    fn update(&mut self, msg: CounterMsg) -> Cmd<Self, CounterMsg> {
+        match msg {
+            CounterMsg::Increment => self.count += 1,
+            CounterMsg::Reset => self.count = 0,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<CounterMsg> {
+        self.views.set(self.views.get() + 1);
+        span(vec![id("counter")], vec![text(self.count)])
+    }
+}
+
+enum Msg {
+    CounterReset,
+}
+
+struct App {
+    resets: u32,
+    views: Rc<Cell<u32>>,
+    counter: SubComponent<Counter, CounterMsg>,
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::CounterReset => self.resets += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        self.views.set(self.views.get() + 1);
+        div(
+            vec![],
+            vec![
+                text(format!("resets: {}", self.resets)),
+                self.counter.view(|msg| {
+                    match msg {
+                        CounterMsg::Reset => Some(Msg::CounterReset),
+                        CounterMsg::Increment => None,
+                    }
+                }),
+            ],
+        )
+    }
+}
+
+/// resolves on the next animation frame
+async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .request_animation_frame(&resolve)
+            .expect("must request animation frame");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+fn counter_text() -> Option<String> {
+    sauron_core::document()
+        .get_element_by_id("counter")
+        .expect("must have the counter")
+        .text_content()
+}
+
+#[wasm_bindgen_test]
+async fn sub_component_is_rendered_on_its_own() {
+    let counter_views = Rc::new(Cell::new(0));
+    let views = Rc::new(Cell::new(0));
+    let program = Program::mount_to_body(App {
+        resets: 0,
+        views: Rc::clone(&views),
+        counter: SubComponent::new(Counter {
+            count: 0,
+            views: Rc::clone(&counter_views),
+        }),
+    });
+    let counter = program
+        .app
+        .borrow()
+        .counter
+        .program()
+        .expect("must be mounted");
+    assert_eq!(counter_text(), Some("0".to_string()));
+    let counter_node = sauron_core::document()
+        .get_element_by_id("counter")
+        .expect("must have the counter");
+
+    // the msgs of the sub component only re-render the sub component
+    counter.dispatch(CounterMsg::Increment);
+    counter.dispatch(CounterMsg::Increment);
+    next_animation_frame().await;
+    assert_eq!(counter.app.borrow().count, 2);
+    assert_eq!(counter_text(), Some("2".to_string()));
+    assert_eq!(counter_views.get(), 2);
+    assert_eq!(views.get(), 1);
+
+    // the mapped msgs are passed to the parent, and the parent view keeps the
+    // nodes of the sub component
+    counter.dispatch(CounterMsg::Reset);
+    next_animation_frame().await;
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().resets, 1);
+    assert_eq!(views.get(), 2);
+    assert_eq!(counter_text(), Some("0".to_string()));
+    assert!(sauron_core::document()
+        .get_element_by_id("counter")
+        .expect("must have the counter")
+        .is_same_node(Some(&counter_node)));
+}
@@ -0,0 +1,53 @@
+#![deny(warnings)]
+use sauron_core::{
+    sub::{
+        Subscription,
+        SubscriptionKey,
+    },
+    Sub,
+};
+
+#[derive(Debug, PartialEq)]
+enum ClockMsg {
+    Tick,
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Clock(ClockMsg),
+    Resized,
+}
+
+#[test]
+fn batched_subscriptions_keep_their_keys() {
+    let sub: Sub<Msg> = Sub::batch(vec![
+        Sub::every(1000, || Msg::Clock(ClockMsg::Tick)),
+        Sub::on_window("resize", |_| Msg::Resized),
+        Sub::none(),
+        Sub::on_document("visibilitychange", |_| Msg::Resized),
+    ]);
+    let keys: Vec<SubscriptionKey> =
+        sub.subscriptions.iter().map(|sub| sub.key()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            SubscriptionKey::Interval(1000),
+            SubscriptionKey::Window("resize"),
+            SubscriptionKey::Document("visibilitychange"),
+        ]
+    );
+}
+
+#[test]
+fn child_subscriptions_are_mapped() {
+    let child_sub: Sub<ClockMsg> = Sub::every(500, || ClockMsg::Tick);
+    let sub: Sub<Msg> = child_sub.map_msg(Msg::Clock);
+    assert_eq!(sub.subscriptions.len(), 1);
+    match &sub.subscriptions[0] {
+        Subscription::Interval(interval, cb) => {
+            assert_eq!(*interval, 500);
+            assert_eq!(cb.emit(()), Msg::Clock(ClockMsg::Tick));
+        }
+        _ => panic!("must be an interval"),
+    }
+}
@@ -0,0 +1,88 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::*,
+    Cmd,
+    Component,
+    Dispatch,
+    Node,
+    Program,
+    Sub,
+};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Resized,
+    ToggleListening,
+}
+
+struct App {
+    resized: u32,
+    is_listening: bool,
+}
+
+impl Component<Msg> for App {
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Resized => self.resized += 1,
+            Msg::ToggleListening => self.is_listening = !self.is_listening,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(vec![], vec![text(self.resized)])
+    }
+
+    fn subscriptions(&self) -> Sub<Msg> {
+        if self.is_listening {
+            Sub::batch(vec![
+                Sub::on_window("resize", |_| Msg::Resized),
+                Sub::every(60_000, || Msg::Resized),
+            ])
+        } else {
+            Sub::none()
+        }
+    }
+}
+
+/// resolves on the next animation frame
+async fn next_animation_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .request_animation_frame(&resolve)
+            .expect("must request animation frame");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+fn resize_window() {
+    let event = web_sys::Event::new("resize").expect("must create event");
+    sauron_core::window()
+        .dispatch_event(&event)
+        .expect("must dispatch event");
+}
+
+#[wasm_bindgen_test]
+async fn listeners_are_removed_when_unsubscribed() {
+    let app = App {
+        resized: 0,
+        is_listening: true,
+    };
+    let program = Program::mount_to_body(app);
+    assert_eq!(program.active_subscription_len(), 2);
+
+    resize_window();
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().resized, 1);
+
+    program.dispatch(Msg::ToggleListening);
+    next_animation_frame().await;
+    assert_eq!(program.active_subscription_len(), 0);
+
+    resize_window();
+    next_animation_frame().await;
+    assert_eq!(program.app.borrow().resized, 1);
+}
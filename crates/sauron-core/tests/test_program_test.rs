@@ -0,0 +1,208 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        events::*,
+        *,
+    },
+    mt_dom::patch::*,
+    Cmd,
+    Component,
+    Dispatch,
+    FireEventError,
+    Node,
+    Selector,
+    TestProgram,
+    Text,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Increment,
+    ChangeName(String),
+    Greet,
+    Hover,
+}
+
+#[derive(Default)]
+struct App {
+    count: i32,
+    name: String,
+    greeting: Option<String>,
+}
+
+impl Component<Msg> for App {
+    fn init(&self) -> Cmd<Self, Msg> {
+        Cmd::new(|program| program.dispatch(Msg::Increment))
+    }
+
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Increment => {
+                self.count += 1;
+                Cmd::none()
+            }
+            Msg::ChangeName(name) => {
+                self.name = name;
+                Cmd::new(|program| program.dispatch(Msg::Greet))
+            }
+            Msg::Greet => {
+                self.greeting = Some(format!("Hello {}!", self.name));
+                Cmd::none()
+            }
+            Msg::Hover => Cmd::no_render(),
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            vec![class("app")],
+            vec![
+                button(
+                    vec![key("increment"), on_click(|_| Msg::Increment)],
+                    vec![text("+")],
+                ),
+                text(self.count),
+                input(
+                    vec![
+                        key("name"),
+                        r#type("text"),
+                        on_input(|event: InputEvent| {
+                            Msg::ChangeName(event.value)
+                        }),
+                    ],
+                    vec![],
+                ),
+                text(self.greeting.as_deref().unwrap_or("")),
+            ],
+        )
+    }
+}
+
+#[test]
+fn init_cmd_is_processed() {
+    let mut program = TestProgram::new(App::default());
+    assert_eq!(program.pending_msgs(), 1);
+    assert_eq!(program.app().count, 0);
+    assert!(program.patches().is_empty());
+
+    assert_eq!(program.run(), 1);
+    assert_eq!(program.app().count, 1);
+    assert_eq!(program.pending_msgs(), 0);
+}
+
+#[test]
+fn input_by_key() {
+    let mut program = TestProgram::new(App::default());
+    program.run();
+
+    program
+        .fire_event(&Selector::key("name"), "input", Some("Sauron"))
+        .expect("must fire");
+    assert!(program.step());
+    assert_eq!(program.app().name, "Sauron");
+    assert_eq!(
+        program
+            .find(&Selector::key("name"))
+            .and_then(|node| node.tag()),
+        Some(&"input")
+    );
+}
+
+#[test]
+fn click_needs_a_browser_event() {
+    let program = TestProgram::new(App::default());
+    let result = program.fire_event(&Selector::key("increment"), "click", None);
+    match result {
+        Err(FireEventError::UnexpectedEvent {
+            selector,
+            event_name,
+            message,
+        }) => {
+            assert_eq!(selector, Selector::key("increment"));
+            assert_eq!(event_name, "click");
+            assert!(message.contains("MouseEvent"), "{}", message);
+        }
+        result => panic!("expecting an unexpected event: {:?}", result),
+    }
+    // only the msg of the init is queued
+    assert_eq!(program.pending_msgs(), 1);
+}
+
+#[test]
+fn dispatch_the_msg_of_a_click() {
+    let mut program = TestProgram::new(App::default());
+    program.run();
+
+    program.dispatch(Msg::Increment);
+    assert!(program.step());
+    assert_eq!(program.app().count, 2);
+    assert_eq!(
+        program.patches(),
+        vec![ChangeText::new(3, &Text::new("1"), 3, &Text::new("2")).into()]
+    );
+    assert_eq!(
+        program
+            .find(&Selector::path(&[1]))
+            .and_then(|node| node.text()),
+        Some("2")
+    );
+}
+
+#[test]
+fn input_by_path_runs_the_returned_cmd() {
+    let mut program = TestProgram::new(App::default());
+    program.run();
+
+    program
+        .fire_event(&Selector::path(&[2]), "input", Some("Sauron"))
+        .expect("must fire");
+    assert!(program.step());
+    assert_eq!(program.app().name, "Sauron");
+    assert_eq!(program.app().greeting, None);
+    assert_eq!(program.pending_msgs(), 1);
+
+    assert!(program.step());
+    assert_eq!(
+        program.patches(),
+        vec![ChangeText::new(
+            5,
+            &Text::new(""),
+            5,
+            &Text::new("Hello Sauron!")
+        )
+        .into()]
+    );
+    assert!(!program.step());
+}
+
+#[test]
+fn no_render_skips_the_view() {
+    let mut program = TestProgram::new(App::default());
+    program.run();
+    program.dispatch(Msg::Increment);
+    program.step();
+    assert!(!program.patches().is_empty());
+
+    program.dispatch(Msg::Hover);
+    assert!(program.step());
+    assert!(program.patches().is_empty());
+}
+
+#[test]
+fn event_without_listener_is_ignored() {
+    let program = TestProgram::new(App::default());
+    program
+        .fire_event(&Selector::path(&[]), "click", None)
+        .expect("must fire");
+    assert_eq!(program.pending_msgs(), 1);
+}
+
+#[test]
+fn event_on_missing_node() {
+    let program = TestProgram::new(App::default());
+    assert_eq!(
+        program.fire_event(&Selector::key("missing"), "click", None),
+        Err(FireEventError::NoNodeMatched(Selector::key("missing")))
+    );
+}
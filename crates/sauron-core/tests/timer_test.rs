@@ -0,0 +1,99 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::*,
+    Cmd,
+    Component,
+    Node,
+    Program,
+    Timer,
+    TimerHandle,
+};
+use std::time::Duration;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+enum Msg {
+    Start,
+    Tick,
+    Stop,
+}
+
+#[derive(Default)]
+struct App {
+    ticks: u32,
+    stopped: bool,
+    ticker: Option<TimerHandle>,
+}
+
+impl Component<Msg> for App {
+    fn init(&self) -> Cmd<Self, Msg> {
+        Cmd::new(|program| program.dispatch(Msg::Start))
+    }
+
+    fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
+        match msg {
+            Msg::Start => {
+                let (tick, ticker) =
+                    Timer::every(Duration::from_millis(10), || Msg::Tick);
+                let (stop, _) =
+                    Timer::after(Duration::from_millis(50), Msg::Stop);
+                self.ticker = Some(ticker);
+                Cmd::batch(vec![tick, stop])
+            }
+            Msg::Tick => {
+                self.ticks += 1;
+                Cmd::none()
+            }
+            Msg::Stop => {
+                self.stopped = true;
+                let ticker = self.ticker.as_ref().expect("must have a ticker");
+                Timer::cancel(ticker)
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(vec![], vec![text(self.ticks)])
+    }
+}
+
+/// resolves after the timeout in milliseconds
+async fn sleep(timeout: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        sauron_core::window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve, timeout,
+            )
+            .expect("must set timeout");
+    });
+    JsFuture::from(promise).await.expect("must resolve");
+}
+
+#[wasm_bindgen_test]
+async fn interval_is_cancelled_by_a_later_cmd() {
+    let program = Program::mount_to_body(App::default());
+    sleep(150).await;
+    let ticks = {
+        let app = program.app.borrow();
+        assert!(app.stopped);
+        assert!(app.ticks > 0);
+        assert!(app.ticker.as_ref().expect("must have a ticker").is_done());
+        app.ticks
+    };
+    sleep(50).await;
+    assert_eq!(program.app.borrow().ticks, ticks);
+}
+
+#[wasm_bindgen_test]
+async fn timer_cancelled_before_emitted_is_never_set() {
+    let program = Program::mount_to_body(App::default());
+    let (cmd, handle): (Cmd<App, Msg>, _) =
+        Timer::after(Duration::from_millis(10), Msg::Tick);
+    handle.cancel().expect("must cancel");
+    cmd.emit(&program);
+    assert!(handle.is_done());
+    sleep(30).await;
+    assert_eq!(program.app.borrow().ticks, 0);
+}
@@ -0,0 +1,76 @@
+#![deny(warnings)]
+use sauron_core::{
+    html::{
+        attributes::*,
+        *,
+    },
+    Node,
+    Render,
+    Router,
+};
+
+#[test]
+fn segments_and_query_are_encoded() {
+    let url = Url::new("/search")
+        .segment("café au lait")
+        .query("q", "a+b=c&d")
+        .query("lang", "日本");
+    assert_eq!(
+        url.to_string(),
+        "/search/caf%C3%A9%20au%20lait?q=a%2Bb%3Dc%26d&lang=%E6%97%A5%E6%9C%AC"
+    );
+}
+
+#[test]
+fn path_without_query() {
+    assert_eq!(Url::new("/").segment("users").to_string(), "/users");
+    assert_eq!(Url::new("").to_string(), "");
+    assert_eq!(Url::new("/users").segment(7).to_string(), "/users/7");
+}
+
+#[test]
+fn parse_query_into_typed_values() {
+    let query = Query::from_search("?page=3&tag=rust&tag=web+dev&debug");
+    assert_eq!(query.parse::<u32>("page"), Some(3));
+    assert_eq!(query.parse::<u32>("tag"), None);
+    assert_eq!(query.get_all("tag"), vec!["rust", "web dev"]);
+    assert_eq!(query.get("debug"), Some(""));
+    assert_eq!(query.get("missing"), None);
+    assert!(Query::from_search("").is_empty());
+}
+
+#[test]
+fn query_round_trip() {
+    let query = Query::new().append("q", "the one & only").append("page", 2);
+    assert_eq!(Query::from_search(&query.to_string()), query);
+}
+
+#[test]
+fn url_in_href() {
+    let view: Node<()> = a(
+        vec![href(Url::new("/users").segment("frodo baggins"))],
+        vec![text("Frodo")],
+    );
+    assert_eq!(
+        view.render_to_string(),
+        r#"<a href="/users/frodo%20baggins">Frodo</a>"#
+    );
+}
+
+#[test]
+fn router_resolves_the_built_url() {
+    let router = Router::new().route("/files/:name", |params| {
+        Some((
+            params.get("name")?.to_string(),
+            params.query_params().get_all("v").len(),
+        ))
+    });
+    let url = Url::new("/files")
+        .segment("a/b c.txt")
+        .query("v", 1)
+        .query("v", 2);
+    assert_eq!(
+        router.resolve(&url.to_string()),
+        Some(("a/b c.txt".to_string(), 2))
+    );
+}
@@ -0,0 +1,211 @@
+#![deny(warnings)]
+//! The connection tests need a local web socket echo server, ie:
+//! `websocat -s 9001`, the url can be changed with `WEBSOCKET_ECHO_URL`
+//! at compile time.
+use sauron_core::{
+    html::text,
+    sub::Subscription,
+    Backoff,
+    Cmd,
+    Component,
+    MsgQueue,
+    Node,
+    SocketEvent,
+    SocketMessage,
+    WebSocket,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::HashMap,
+    rc::Rc,
+};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const ECHO_URL: &str = match option_env!("WEBSOCKET_ECHO_URL") {
+    Some(url) => url,
+    None => "ws://127.0.0.1:9001",
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Price {
+    symbol: String,
+    cents: u64,
+}
+
+struct App;
+
+impl Component<SocketEvent> for App {
+    fn update(&mut self, _msg: SocketEvent) -> Cmd<Self, SocketEvent> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<SocketEvent> {
+        text("")
+    }
+}
+
+async fn sleep(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        web_sys::window()
+            .expect("must have window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+            .expect("must set timeout");
+    });
+    JsFuture::from(promise).await.expect("must sleep");
+}
+
+/// wait until the queue has the number of msgs
+async fn wait_for(queue: &MsgQueue<SocketEvent>, count: usize) {
+    for _ in 0..100 {
+        if queue.len() >= count {
+            return;
+        }
+        sleep(20).await;
+    }
+}
+
+#[wasm_bindgen_test]
+fn backoff_is_doubled_up_to_the_max() {
+    let backoff = Backoff {
+        initial: 100,
+        max: 1000,
+        max_attempts: Some(6),
+    };
+    let delays: Vec<Option<i32>> = (0..7).map(|i| backoff.delay(i)).collect();
+    assert_eq!(
+        delays,
+        vec![
+            Some(100),
+            Some(200),
+            Some(400),
+            Some(800),
+            Some(1000),
+            Some(1000),
+            None
+        ]
+    );
+    assert_eq!(Backoff::default().delay(64), Some(30_000));
+}
+
+#[wasm_bindgen_test]
+fn decode_json_messages() {
+    let text = SocketEvent::Message(SocketMessage::Text(
+        r#"{"symbol": "ACME", "cents": 1250}"#.to_string(),
+    ));
+    let price = Price {
+        symbol: "ACME".to_string(),
+        cents: 1250,
+    };
+    assert_eq!(text.json::<Price>().map(Result::ok), Some(Some(price)));
+
+    let binary =
+        SocketMessage::Binary(br#"{"symbol": "ACME", "cents": 1}"#.to_vec());
+    assert_eq!(
+        binary.json::<Price>().map(|price| price.cents).ok(),
+        Some(1)
+    );
+
+    assert!(SocketEvent::Open.json::<Price>().is_none());
+    assert!(matches!(
+        SocketEvent::Message(SocketMessage::Text("{".to_string()))
+            .json::<Price>(),
+        Some(Err(_))
+    ));
+}
+
+#[wasm_bindgen_test]
+fn send_json_of_a_value_which_can_not_be_serialized() {
+    let socket = WebSocket::new(ECHO_URL);
+    let mut by_position = HashMap::new();
+    by_position.insert((0, 0), "origin");
+    let result: Result<Cmd<App, SocketEvent>, _> =
+        socket.send_json(&by_position);
+    assert!(result.is_err());
+    assert!(!socket.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn echo_text_binary_and_json() {
+    let socket = WebSocket::new(ECHO_URL);
+    let queue = MsgQueue::new();
+    let sub = socket.subscribe(|event| event);
+    let connection = match &sub.subscriptions[0] {
+        Subscription::Custom(custom) => custom.start(Rc::new(queue.clone())),
+        _ => panic!("must be a custom subscription"),
+    };
+
+    // sent before the connection is opened, and is sent once opened
+    let price = Price {
+        symbol: "ACME".to_string(),
+        cents: 1250,
+    };
+    let cmds: Vec<Cmd<App, SocketEvent>> = vec![
+        socket.send_text("hello"),
+        socket.send_binary(&[1, 2, 3]),
+        socket.send_json(&price).expect("must serialize"),
+    ];
+    for cmd in cmds {
+        cmd.emit(&queue);
+    }
+    wait_for(&queue, 4).await;
+
+    let events = queue.take();
+    assert_eq!(events[0], SocketEvent::Open);
+    assert_eq!(
+        events[1],
+        SocketEvent::Message(SocketMessage::Text("hello".to_string()))
+    );
+    assert_eq!(
+        events[2],
+        SocketEvent::Message(SocketMessage::Binary(vec![1, 2, 3]))
+    );
+    assert_eq!(events[3].json::<Price>().map(Result::ok), Some(Some(price)));
+    assert!(socket.is_open());
+
+    // the connection is closed when the subscription is removed
+    drop(connection);
+    assert!(!socket.is_open());
+    sleep(50).await;
+    assert!(queue.is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn reconnect_with_backoff() {
+    let socket = WebSocket::new("ws://127.0.0.1:1").reconnect(Backoff {
+        initial: 10,
+        max: 10,
+        max_attempts: Some(2),
+    });
+    let queue = MsgQueue::new();
+    let sub = socket.subscribe(|event| event);
+    let _connection = match &sub.subscriptions[0] {
+        Subscription::Custom(custom) => custom.start(Rc::new(queue.clone())),
+        _ => panic!("must be a custom subscription"),
+    };
+    sleep(500).await;
+
+    let reconnecting: Vec<SocketEvent> = queue
+        .take()
+        .into_iter()
+        .filter(|event| matches!(event, SocketEvent::Reconnecting { .. }))
+        .collect();
+    assert_eq!(
+        reconnecting,
+        vec![
+            SocketEvent::Reconnecting {
+                attempt: 1,
+                delay: 10
+            },
+            SocketEvent::Reconnecting {
+                attempt: 2,
+                delay: 10
+            },
+        ]
+    );
+}
@@ -361,7 +361,7 @@ mod tests {
     }
 ```
         "#;
-        let expected = "<pre>\n    <code class=\"rust\">    fn main(){\n        println!(\"Hello world!\");\n    }\n</code>\n</pre>";
+        let expected = "<pre><code class=\"rust\">    fn main(){\n        println!(\"Hello world!\");\n    }\n</code></pre>";
         let view: Node<()> = markdown(md);
 
         let mut buffer = String::new();
@@ -375,7 +375,7 @@ mod tests {
         let md = r#"
 This is has some `code` and other..
         "#;
-        let expected = "<p>\n    This is has some \n    <code>code</code>\n     and other..\n</p>";
+        let expected = "<p>This is has some <code>code</code> and other..</p>";
         let view: Node<()> = markdown(md);
 
         let mut buffer = String::new();
@@ -404,7 +404,7 @@ Duplicated footnote reference[^second].
 [^second]: Footnote text.
         "#;
 
-        let expected = "<p>\n    <h3>\n        <a href=\"https://github.com/markdown-it/markdown-it-footnote\" title=\"\">Footnotes</a>\n    </h3>\n    <p>\n        Footnote 1 link\n        <sup class=\"footnote-reference\">\n            <a href=\"#first\">1</a>\n        </sup>\n        .\n    </p>\n    <p>\n        Footnote 2 link\n        <sup class=\"footnote-reference\">\n            <a href=\"#second\">2</a>\n        </sup>\n        .\n    </p>\n    <p>\n        Inline footnote^\n        [\n        Text of inline footnote\n        ]\n         definition.\n    </p>\n    <p>\n        Duplicated footnote reference\n        <sup class=\"footnote-reference\">\n            <a href=\"#second\">2</a>\n        </sup>\n        .\n    </p>\n    <footer class=\"footnote-definition\" id=\"first\">\n        <sup class=\"footnote-label\">1</sup>\n        <p>\n            Footnote \n            <strong>can have markup</strong>\n        </p>\n    </footer>\n    <pre>\n        <code>and multiple paragraphs.\n</code>\n    </pre>\n    <footer class=\"footnote-definition\" id=\"second\">\n        <sup class=\"footnote-label\">2</sup>\n        <p>Footnote text.</p>\n    </footer>\n</p>";
+        let expected = "<p>\n    <h3><a href=\"https://github.com/markdown-it/markdown-it-footnote\" title=\"\">Footnotes</a></h3>\n    <p>Footnote 1 link<sup class=\"footnote-reference\"><a href=\"#first\">1</a></sup>.</p>\n    <p>Footnote 2 link<sup class=\"footnote-reference\"><a href=\"#second\">2</a></sup>.</p>\n    <p>Inline footnote^[Text of inline footnote] definition.</p>\n    <p>Duplicated footnote reference<sup class=\"footnote-reference\"><a href=\"#second\">2</a></sup>.</p>\n    <footer class=\"footnote-definition\" id=\"first\"><sup class=\"footnote-label\">1</sup><p>Footnote <strong>can have markup</strong></p></footer>\n    <pre><code>and multiple paragraphs.\n</code></pre>\n    <footer class=\"footnote-definition\" id=\"second\"><sup class=\"footnote-label\">2</sup><p>Footnote text.</p></footer>\n</p>";
         let view: Node<()> = markdown(md);
 
         let mut buffer = String::new();
@@ -420,7 +420,7 @@ Duplicated footnote reference[^second].
 <img src="img.jpeg"/>"#;
 
         let expected =
-"<p>\n    <p>\n        <a href=\"link.html\" title=\"\">Hello</a>\n        \n\n    </p>\n    <img src=\"img.jpeg\"/>\n</p>";
+"<p><p><a href=\"link.html\" title=\"\">Hello</a>\n</p><img src=\"img.jpeg\"/></p>";
         let view: Node<()> = markdown(md);
 
         let mut buffer = String::new();
@@ -436,7 +436,7 @@ Duplicated footnote reference[^second].
 ![](img.jpeg "Image title")"#;
 
         let expected =
-            "<p>\n    <a href=\"link.html\" title=\"\">Hello</a>\n    \n\n    <img src=\"img.jpeg\" title=\"Image title\"/>\n</p>";
+            "<p><a href=\"link.html\" title=\"\">Hello</a>\n<img src=\"img.jpeg\" title=\"Image title\"/></p>";
         let view: Node<()> = markdown(md);
 
         let mut buffer = String::new();
@@ -501,12 +501,8 @@ look like:
 [link with title](http://nodeca.github.io/pica/demo/ "title text!")"#;
         let view: Node<()> = markdown(md);
         let expected = r#"<p>
-    <p>
-        <a href="http://dev.nodeca.com" title="">link text</a>
-    </p>
-    <p>
-        <a href="http://nodeca.github.io/pica/demo/" title="title text!">link with title</a>
-    </p>
+    <p><a href="http://dev.nodeca.com" title="">link text</a></p>
+    <p><a href="http://nodeca.github.io/pica/demo/" title="title text!">link with title</a></p>
 </p>"#;
 
         let mut buffer = String::new();
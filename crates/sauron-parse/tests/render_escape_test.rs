@@ -0,0 +1,100 @@
+use sauron_core::{
+    html::{
+        attributes::attr,
+        div,
+        iframe,
+        input,
+        script,
+        text,
+    },
+    Node,
+    Render,
+};
+use sauron_parse::parse_simple;
+
+/// strings which breaks the markup when they are rendered without escaping
+const HOSTILE: [&str; 6] = [
+    r#""><script>alert('xss')</script>"#,
+    "</div><img src=x onerror=alert(1)>",
+    r#"a & b &amp; c < d > e "f" 'g'"#,
+    "<!-- not a comment -->",
+    "&lt;already escaped&gt; and &nbsp;",
+    "non\u{a0}breaking",
+];
+
+fn title_of(node: &Node<()>) -> Option<&str> {
+    node.get_attribute_value(&"title")?
+        .first()?
+        .get_simple()?
+        .as_str()
+}
+
+#[test]
+fn hostile_text_and_attribute_round_trip() {
+    for hostile in HOSTILE.iter() {
+        let view: Node<()> = div(
+            vec![attr("title", *hostile)],
+            vec![text(hostile), input(vec![attr("title", *hostile)], vec![])],
+        );
+        let html = view.render_to_string();
+
+        let nodes: Vec<Node<()>> = parse_simple(&html).expect("must parse");
+        assert_eq!(nodes.len(), 1, "{} must be one element", html);
+        let parsed = &nodes[0];
+        assert_eq!(parsed.tag(), Some(&"div"));
+        assert_eq!(title_of(parsed), Some(*hostile));
+
+        let children = parsed.get_children().expect("must have children");
+        assert_eq!(children.len(), 2, "{} must have 2 children", html);
+        assert_eq!(children[0].text(), Some(*hostile));
+        assert_eq!(children[1].tag(), Some(&"input"));
+        assert_eq!(title_of(&children[1]), Some(*hostile));
+
+        assert_eq!(html, parsed.render_to_string());
+    }
+}
+
+#[test]
+fn script_can_not_be_closed_by_its_text() {
+    let code = "if (a < b && c) { s = '</script><b>injected</b>'; }";
+    let view: Node<()> = div(
+        vec![],
+        vec![script(vec![], vec![text(code)]), div(vec![], vec![])],
+    );
+    let html = view.render_to_string();
+
+    let nodes: Vec<Node<()>> = parse_simple(&html).expect("must parse");
+    let children = nodes[0].get_children().expect("must have children");
+    assert_eq!(children.len(), 2, "{} must have 2 children", html);
+    assert_eq!(children[0].tag(), Some(&"script"));
+    assert_eq!(
+        children[0].get_children().expect("must have the code")[0].text(),
+        Some(r"if (a < b && c) { s = '<\/script><b>injected</b>'; }")
+    );
+    assert_eq!(children[1].tag(), Some(&"div"));
+}
+
+#[test]
+fn iframe_can_not_be_closed_by_its_text() {
+    let view: Node<()> = div(
+        vec![],
+        vec![
+            iframe(
+                vec![],
+                vec![text("</IFRAME><script>alert('xss')</script>")],
+            ),
+            div(vec![], vec![]),
+        ],
+    );
+    let html = view.render_to_string();
+
+    let nodes: Vec<Node<()>> = parse_simple(&html).expect("must parse");
+    let children = nodes[0].get_children().expect("must have children");
+    assert_eq!(children.len(), 2, "{} must have 2 children", html);
+    assert_eq!(children[0].tag(), Some(&"iframe"));
+    assert_eq!(
+        children[0].get_children().expect("must have the text")[0].text(),
+        Some(r"<\/IFRAME><script>alert('xss')</script>")
+    );
+    assert_eq!(children[1].tag(), Some(&"div"));
+}
@@ -8,8 +8,9 @@ use js_sys::Date;
 use sauron::{
     html::attributes::style,
     prelude::*,
-    wasm_bindgen::JsCast,
+    Timer,
 };
+use std::time::Duration;
 
 #[macro_use]
 extern crate log;
@@ -31,23 +32,11 @@ impl Clock {
 }
 
 impl Component<Msg> for Clock {
-    // we wire the window set_interval api to trigger an Msg::Tick
-    // by dispatching it from the program, through the Cmd interface
+    // we trigger a Msg::Tick every 30ms using the Timer cmd
     fn init(&self) -> Cmd<Self, Msg> {
-        Cmd::new(move |program| {
-            let clock: Closure<dyn Fn()> = Closure::wrap(Box::new(move || {
-                program.dispatch(Msg::Tick);
-            }));
-
-            web_sys::window()
-                .expect("no global `window` exists")
-                .set_interval_with_callback_and_timeout_and_arguments_0(
-                    clock.as_ref().unchecked_ref(),
-                    30,
-                )
-                .expect("Unable to start interval");
-            clock.forget();
-        })
+        let (tick, _handle) =
+            Timer::every(Duration::from_millis(30), || Msg::Tick);
+        tick
     }
 
     fn update(&mut self, msg: Msg) -> Cmd<Self, Msg> {
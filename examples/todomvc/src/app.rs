@@ -8,6 +8,9 @@ use sauron::{
     Cmd,
     Component,
     Node,
+    Storage,
+    StorageError,
+    Sub,
 };
 use serde_derive::{
     Deserialize,
@@ -48,6 +51,9 @@ pub enum Msg {
     ToggleEdit(usize),
     Toggle(usize),
     ClearCompleted,
+    /// the entries are changed in the other tabs
+    Synced(Result<Option<Model>, StorageError>),
+    StorageError(StorageError),
     NoOp,
 }
 
@@ -105,11 +111,31 @@ impl Component<Msg> for Model {
             Msg::ClearCompleted => {
                 self.entries.retain(|entry| !entry.completed);
             }
+            Msg::Synced(Ok(model)) => {
+                let model = model.unwrap_or_else(Self::new);
+                self.entries = model.entries;
+                self.uid = model.uid;
+                return Cmd::none();
+            }
+            Msg::Synced(Err(err)) | Msg::StorageError(err) => {
+                log::error!("Could not sync with local storage, {}", err);
+                return Cmd::no_render();
+            }
             Msg::NoOp => {}
         }
-        #[cfg(feature = "with-storage")]
-        self.save_to_storage();
-        Cmd::none()
+        if cfg!(feature = "with-storage") {
+            storage().save("data", &self, Msg::StorageError)
+        } else {
+            Cmd::none()
+        }
+    }
+
+    fn subscriptions(&self) -> Sub<Msg> {
+        if cfg!(feature = "with-storage") {
+            storage().on_change("data", Msg::Synced)
+        } else {
+            Sub::none()
+        }
     }
 
     fn view(&self) -> Node<Msg> {
@@ -370,38 +396,23 @@ impl Model {
         )
     }
 
-    #[allow(unused)]
-    fn save_to_storage(&self) {
-        let window = web_sys::window().expect("no global `window` exists");
-        let local_storage = window.local_storage();
-        if let Ok(Some(local_storage)) = local_storage {
-            let json_data =
-                serde_json::to_string(&self).expect("must serialize data");
-            if let Err(err) =
-                local_storage.set_item("todomvc::data", &json_data)
-            {
-                log::error!("Could not write to local storage, {:?}", err);
-            }
-        }
-    }
-
     #[allow(unused)]
     pub fn get_from_storage() -> Self {
-        let window = web_sys::window().expect("no global `window` exists");
-        let local_storage = window.local_storage();
-
-        if let Ok(Some(local_storage)) = local_storage {
-            if let Ok(Some(s)) = local_storage.get_item("todomvc::data") {
-                serde_json::from_str(&s).ok().unwrap_or(Self::new())
-            } else {
+        match storage().get("data") {
+            Ok(model) => model.unwrap_or_else(Self::new),
+            Err(err) => {
+                log::error!("Could not read from local storage, {}", err);
                 Self::new()
             }
-        } else {
-            Self::new()
         }
     }
 }
 
+/// the entries are stored in `todomvc::data` of the local storage
+fn storage() -> Storage {
+    Storage::local("todomvc")
+}
+
 impl ToString for Visibility {
     fn to_string(&self) -> String {
         match self {
@@ -173,7 +173,7 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
     pub use sauron_core::dom::*;
     pub use sauron_core::web_sys;
     pub use sauron_core::wasm_bindgen;
-    pub use sauron_core::{Component, Cmd, Program};
+    pub use sauron_core::Program;
     pub use sauron_core::js_sys;
 }}
 
@@ -184,8 +184,11 @@ pub mod prelude {
     pub use sauron_node_macro::node;
 }
 pub use sauron_core::{
-    diff, html, jss, mt_dom, svg, Attribute, Callback, Element, Node, Patch,
-    Render, Text,
+    cmd, diff, html, jss, mt_dom, render_to_string_with_state,
+    render_with_state, sub, svg, Attribute, Callback, ChunkWriter, Cmd,
+    Component, Dispatch, Element, HtmlDocument, MsgQueue, Node, Patch, Render,
+    RenderOptions, RouteParams, Router, Selector, StreamRenderer, Sub,
+    TestProgram, Text, SERIALIZED_STATE_ID,
 };
 
 // reexports
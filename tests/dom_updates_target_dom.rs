@@ -166,9 +166,12 @@ fn multiple_match_on_keyed_elements() {
         &simple_program,
         current_dom.clone(),
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
-    dom_updater.patch_dom(&simple_program, patches);
+    dom_updater
+        .patch_dom(&simple_program, patches)
+        .expect("must patch the dom");
 
     let app_node = crate::document()
         .query_selector(".app")
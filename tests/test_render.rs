@@ -15,7 +15,7 @@ fn test_render() {
         vec![article(vec![inner_html("<h1>Lorep Ipsum</h1>")], vec![])],
     );
 
-    let expected = r#"<main class="container"><article ><h1>Lorep Ipsum</h1></article></main>"#;
+    let expected = r#"<main class="container"><article><h1>Lorep Ipsum</h1></article></main>"#;
 
     assert_eq!(expected, view1.render_to_string());
 }
@@ -176,9 +176,12 @@ fn test1() {
         &simple_program,
         current_dom.clone(),
         &sauron_core::body(),
-    );
+    )
+    .expect("must mount");
 
-    dom_updater.patch_dom(&simple_program, patch);
+    dom_updater
+        .patch_dom(&simple_program, patch)
+        .expect("must patch the dom");
 
     let app_node = crate::document()
         .query_selector(".app")